@@ -0,0 +1,25 @@
+#![feature(test)]
+
+extern crate test;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate argon2;
+
+mod password_hash {
+    mod argon2id {
+        use argon2;
+        use crypto_bench;
+
+        argon2id_benches!(m_cost, t_cost, p_cost, out, {
+            let params = argon2::Params::new(m_cost, t_cost, p_cost,
+                                             Some(out.len())).unwrap();
+            let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id,
+                                             argon2::Version::V0x13, params);
+            argon2.hash_password_into(crypto_bench::password_hash::PASSWORD,
+                                      crypto_bench::password_hash::SALT,
+                                      &mut out).unwrap();
+        });
+    }
+}
@@ -0,0 +1,19 @@
+// Prints the peak memory use of the `argon2` crate's Argon2id for each set of
+// parameters that is benchmarked. See `crypto_bench::peak_memory`.
+
+#[macro_use]
+extern crate crypto_bench;
+extern crate argon2;
+
+fn main() {
+    argon2id_params!(argon2id_peak_memory, "argon2", m_cost, t_cost, p_cost,
+                     out, {
+        let params = argon2::Params::new(m_cost, t_cost, p_cost,
+                                         Some(out.len())).unwrap();
+        let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id,
+                                         argon2::Version::V0x13, params);
+        argon2.hash_password_into(crypto_bench::password_hash::PASSWORD,
+                                  crypto_bench::password_hash::SALT,
+                                  &mut out).unwrap();
+    });
+}
@@ -1,3 +1,7 @@
+PUSHD argon2
+cargo %*
+POPD
+
 PUSHD fastpbkdf2
 cargo %*
 POPD
@@ -17,3 +21,10 @@ POPD
 PUSHD rust_crypto
 cargo %*
 POPD
+
+PUSHD rustcrypto_aeads
+cargo %*
+POPD
+PUSHD rustcrypto_macs
+cargo %*
+POPD
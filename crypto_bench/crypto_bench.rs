@@ -2,26 +2,1508 @@
 // `main` to avoid things complaining.
 
 pub mod aead {
-    // All the AEADs we're testing use 96-bit nonces.
+    // Most of the AEADs we're testing use 96-bit nonces and 128-bit tags. The
+    // ones that don't use `Lengths` instead.
     pub const NONCE: [u8; 96 / 8] = [0u8; 96 / 8];
 
+    pub const MAX_NONCE_LEN: usize = 192 / 8;
+
+    // The nonce and tag lengths of an AEAD.
+    #[derive(Clone, Copy)]
+    pub struct Lengths {
+        pub nonce: usize,
+        pub tag: usize,
+    }
+
+    impl Lengths {
+        // An all-zero nonce of the right length, like `NONCE`.
+        pub fn nonce(&self) -> &'static [u8] {
+            static NONCE: [u8; MAX_NONCE_LEN] = [0u8; MAX_NONCE_LEN];
+            &NONCE[..self.nonce]
+        }
+    }
+
+    // AES-CCM as used in TLS (RFC 6655), with 96-bit nonces, which limits
+    // messages to 2^24 - 1 bytes. AES-CCM_8 truncates the tag to 64 bits.
+    pub const AES_CCM: Lengths = Lengths { nonce: 96 / 8, tag: 128 / 8 };
+    pub const AES_CCM_8: Lengths = Lengths { nonce: 96 / 8, tag: 64 / 8 };
+
     // A TLS 1.2 finished message is always 12 bytes long.
     pub const TLS12_FINISHED_LEN: usize = 12;
 
-    // A TLS 1.3 finished message is "[t]he size of the HMAC output for the
-    // Hash used for the handshake," which is usually SHA-256.
-    pub const TLS13_FINISHED_LEN: usize = 32;
+    // A TLS 1.3 finished message is "[t]he size of the HMAC output for the
+    // Hash used for the handshake," which is usually SHA-256.
+    pub const TLS13_FINISHED_LEN: usize = 32;
+
+    // In TLS 1.2, 13 bytes of additional data are used for AEAD cipher suites.
+    pub const TLS12_AD: [u8; 13] = [
+        23,         // Type: application_data
+        3, 3,       // Version = TLS 1.2.
+        0x12, 0x34, // Length = 0x1234.
+        0, 0, 0, 0, 0, 0, 0, 1, // Record #1
+    ];
+
+    // In TLS 1.3, no additional data is used for AEAD cipher suites.
+    pub const TLS13_AD: [u8; 0] = [ ];
+
+    #[derive(Clone, Copy)]
+    pub enum TlsVersion {
+        Tls12,
+        Tls13,
+    }
+
+    // The nonces and additional data of consecutive records, constructed the
+    // way TLS constructs them, so that the benchmarks don't reuse a nonce.
+    //
+    // In TLS 1.2, the nonce is a 4-byte implicit part followed by an 8-byte
+    // explicit part, which is the sequence number in practice, and the
+    // sequence number is part of the additional data. In TLS 1.3, the nonce is
+    // the static IV XOR the sequence number and there is no additional data.
+    // (ChaCha20-Poly1305 cipher suites construct the nonce the TLS 1.3 way in
+    // TLS 1.2 too, but the two constructions cost about the same.)
+    pub struct Records {
+        version: TlsVersion,
+        plaintext_len: usize,
+        sequence_number: u64,
+        nonce: [u8; 96 / 8],
+        ad: [u8; 13],
+    }
+
+    // In real connections the IV is derived from the key block or the traffic
+    // secret along with the key.
+    pub const TLS_IV: [u8; 96 / 8] = [
+        0x8c, 0x6b, 0x3c, 0x3b, 0x25, 0x9e, 0x01, 0x40, 0xd2, 0xb8, 0x11, 0x5f
+    ];
+
+    impl Records {
+        // `plaintext_len` is the length of each record's plaintext, which is
+        // part of TLS 1.2's additional data.
+        pub fn new(version: TlsVersion, plaintext_len: usize) -> Records {
+            let mut records = Records {
+                version: version,
+                plaintext_len: plaintext_len,
+                sequence_number: 0,
+                nonce: TLS_IV,
+                ad: TLS12_AD,
+            };
+            records.update();
+            records
+        }
+
+        // Advances to the next record.
+        pub fn advance(&mut self) {
+            self.sequence_number += 1;
+            self.update();
+        }
+
+        pub fn nonce(&self) -> &[u8; 96 / 8] { &self.nonce }
+
+        pub fn ad(&self) -> &[u8] {
+            match self.version {
+                TlsVersion::Tls12 => &self.ad,
+                TlsVersion::Tls13 => &TLS13_AD,
+            }
+        }
+
+        fn update(&mut self) {
+            let mut seq = [0u8; 8];
+            for i in 0..8 {
+                seq[i] = (self.sequence_number >> (56 - (8 * i))) as u8;
+            }
+            match self.version {
+                TlsVersion::Tls12 => {
+                    self.nonce[4..].copy_from_slice(&seq);
+                    self.ad = tls12_ad(self.sequence_number,
+                                       self.plaintext_len);
+                },
+                TlsVersion::Tls13 => {
+                    for i in 0..8 {
+                        self.nonce[4 + i] = TLS_IV[4 + i] ^ seq[i];
+                    }
+                },
+            }
+        }
+    }
+
+    // The additional data of a TLS 1.2 record (RFC 5246 Section 6.2.3.3):
+    // seq_num || type || version || length.
+    pub fn tls12_ad(sequence_number: u64, plaintext_len: usize) -> [u8; 13] {
+        let mut ad = [0u8; 13];
+        for i in 0..8 {
+            ad[i] = (sequence_number >> (56 - (8 * i))) as u8;
+        }
+        ad[8] = 23; // Type: application_data
+        ad[9] = 3; // Version = TLS 1.2.
+        ad[10] = 3;
+        ad[11] = (plaintext_len >> 8) as u8;
+        ad[12] = plaintext_len as u8;
+        ad
+    }
+
+    // Used with an empty plaintext to measure the cost of authenticating
+    // additional data alone (GHASH or Poly1305, without any encryption).
+    pub const LARGE_AD: [u8; 8192] = [0u8; 8192];
+
+    // The byte counts that an AEAD's throughput can be calculated from.
+    // libtest's `Bencher` has only one byte counter, so the success paths are
+    // benchmarked once for each of them, in benchmarks named after them.
+    #[derive(Clone, Copy, PartialEq)]
+    pub enum Counter {
+        // The bytes encrypted or decrypted.
+        Plaintext,
+        // The additional data bytes.
+        Ad,
+        // The plaintext and additional data bytes.
+        Authenticated,
+        // The plaintext and tag bytes, i.e. the sealed record.
+        Wire,
+    }
+
+    impl Counter {
+        pub fn name(&self) -> &'static str {
+            match *self {
+                Counter::Plaintext => "plaintext",
+                Counter::Ad => "ad",
+                Counter::Authenticated => "authenticated",
+                Counter::Wire => "wire",
+            }
+        }
+    }
+
+    // The counters that the success path of sealing or opening a
+    // `plaintext_len`-byte plaintext is reported by. When there's no
+    // plaintext, as in the `ad_only` scenarios, only the additional data is
+    // counted. The authentication failure paths are only reported by the
+    // first one.
+    pub fn counters(plaintext_len: usize) -> &'static [Counter] {
+        if plaintext_len == 0 {
+            &[Counter::Ad]
+        } else {
+            &[Counter::Plaintext, Counter::Ad, Counter::Authenticated,
+              Counter::Wire]
+        }
+    }
+
+    // The sizes of the inputs and outputs of a single seal or open operation.
+    #[derive(Clone, Copy)]
+    pub struct ByteCounts {
+        pub plaintext: usize,
+        pub ad: usize,
+        pub tag: usize,
+    }
+
+    impl ByteCounts {
+        pub fn new(plaintext: usize, ad: usize, tag: usize) -> ByteCounts {
+            ByteCounts { plaintext: plaintext, ad: ad, tag: tag }
+        }
+
+        pub fn authenticated(&self) -> usize { self.plaintext + self.ad }
+
+        pub fn wire(&self) -> usize { self.plaintext + self.tag }
+
+        // The value to assign to `Bencher::bytes`.
+        pub fn bytes(&self, counter: Counter) -> u64 {
+            let bytes = match counter {
+                Counter::Plaintext => self.plaintext,
+                Counter::Ad => self.ad,
+                Counter::Authenticated => self.authenticated(),
+                Counter::Wire => self.wire(),
+            };
+            bytes as u64
+        }
+    }
+
+    // The ways that the benchmarks of the authentication failure path tamper
+    // with a sealed record before opening it. `None` is the success path.
+    #[derive(Clone, Copy, PartialEq)]
+    pub enum Tampering {
+        None,
+        Ad,
+        Body,
+        Tag,
+    }
+
+    impl Tampering {
+        // The name of the benchmark of opening a record tampered with this
+        // way.
+        pub fn name(&self) -> &'static str {
+            match *self {
+                Tampering::None => "valid",
+                Tampering::Ad => "bad_ad",
+                Tampering::Body => "bad_body",
+                Tampering::Tag => "bad_tag",
+            }
+        }
+    }
+
+    // The ways that a record with a `chunk_len`-byte body can be tampered
+    // with. There's no body to tamper with when the plaintext is empty.
+    pub fn tamperings(chunk_len: usize) -> &'static [Tampering] {
+        if chunk_len == 0 {
+            &[Tampering::None, Tampering::Ad, Tampering::Tag]
+        } else {
+            &[Tampering::None, Tampering::Ad, Tampering::Body, Tampering::Tag]
+        }
+    }
+
+    // The names of the benchmarks of opening a `chunk_len`-byte record
+    // tampered with `tampering`, and the counters they are reported by, as
+    // `aead_open_in_place_bench` names them.
+    pub fn open_benches(tampering: Tampering, chunk_len: usize)
+                        -> Vec<(String, Counter)> {
+        let counters = counters(chunk_len);
+        if tampering == Tampering::None {
+            counters.iter()
+                .map(|counter| {
+                    (format!("{}::{}", tampering.name(), counter.name()),
+                     *counter)
+                })
+                .collect()
+        } else {
+            vec![(tampering.name().to_string(), counters[0])]
+        }
+    }
+
+    // Tampers with the additional data, the encrypted body, or the tag of a
+    // sealed record. Empty additional data (as in TLS 1.3) is tampered with
+    // by adding a byte to it. An empty body can't be tampered with.
+    pub fn tamper(tampering: Tampering, ad: &mut Vec<u8>, body: &mut [u8],
+                  tag: &mut [u8]) {
+        match tampering {
+            Tampering::None => {},
+            Tampering::Ad => {
+                if ad.is_empty() {
+                    ad.push(0);
+                } else {
+                    ad[0] ^= 1;
+                }
+            },
+            Tampering::Body => { body[0] ^= 1; },
+            Tampering::Tag => { tag[0] ^= 1; },
+        }
+    }
+}
+
+// The `seal_in_place` and `open_in_place` benchmarks of an AEAD. `$f` is the
+// function that implements the benchmark, in the module that invokes
+// `aead_benches!`; it is called with the `$arg`s followed by the scenario's
+// plaintext length, its additional data, the `aead::Counter` to report, and
+// the `test::Bencher` (for `aead_open_in_place_bench`, the `aead::Tampering`
+// comes before the `Counter`). Key setup is done once per connection, not once
+// per record, so `$f` should do it before the timing starts.
+//
+// Each scenario is a module with a benchmark for each of
+// `aead::counters($chunk_len)`, named after it.
+#[macro_export]
+macro_rules! aead_seal_in_place_bench {
+    // Only the additional data is counted when the plaintext is empty.
+    ( $scenario:ident, 0, $ad:expr, $f:ident $(, $arg:expr)* ) => {
+        mod $scenario {
+            use test;
+
+            aead_counter_bench!(ad, $crate::aead::Counter::Ad,
+                                super::super::$f $(, $arg)*, 0, $ad);
+        }
+    };
+    ( $scenario:ident, $chunk_len:expr, $ad:expr,
+      $f:ident $(, $arg:expr)* ) => {
+        mod $scenario {
+            use test;
+
+            aead_counter_benches!(super::super::$f $(, $arg)*, $chunk_len,
+                                  $ad);
+        }
+    }
+}
+
+// A benchmark that calls `$f` with the `$arg`s followed by `$counter` and
+// the `test::Bencher`.
+#[macro_export]
+macro_rules! aead_counter_bench {
+    ( $bench_fn_name:ident, $counter:expr, $f:path $(, $arg:expr)* ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            $f($($arg, )* $counter, b);
+        }
+    }
+}
+
+// `aead_counter_bench` for each counter of a non-empty plaintext.
+#[macro_export]
+macro_rules! aead_counter_benches {
+    ( $f:path $(, $arg:expr)* ) => {
+        aead_counter_bench!(plaintext, $crate::aead::Counter::Plaintext,
+                            $f $(, $arg)*);
+        aead_counter_bench!(ad, $crate::aead::Counter::Ad, $f $(, $arg)*);
+        aead_counter_bench!(authenticated,
+                            $crate::aead::Counter::Authenticated,
+                            $f $(, $arg)*);
+        aead_counter_bench!(wire, $crate::aead::Counter::Wire, $f $(, $arg)*);
+    }
+}
+
+// Each scenario is a module so that the timings of the authentication failure
+// paths are reported next to the timings of the success path (`valid`). The
+// success path is a module with a benchmark for each counter, like
+// `aead_seal_in_place_bench`; the failure paths are only reported by the
+// first of `aead::counters($chunk_len)`.
+//
+// Opening in place overwrites the ciphertext with the plaintext, and
+// `test::Bencher` can't restore it before each open without timing the copy,
+// so this is only for implementations that decrypt into a separate buffer.
+// The others register Criterion benchmarks with `aead_scenario_call`, and
+// restore the ciphertext in `iter_batched_ref`'s untimed setup. They are
+// named by `aead::open_benches`.
+#[macro_export]
+macro_rules! aead_open_in_place_bench {
+    // There's no body to tamper with when the plaintext is empty.
+    ( $scenario:ident, 0, $ad:expr, $f:ident $(, $arg:expr)* ) => {
+        mod $scenario {
+            use test;
+
+            mod valid {
+                use test;
+
+                aead_counter_bench!(ad, $crate::aead::Counter::Ad,
+                                    super::super::super::$f $(, $arg)*, 0,
+                                    $ad, $crate::aead::Tampering::None);
+            }
+
+            aead_counter_bench!(bad_ad, $crate::aead::Counter::Ad,
+                                super::super::$f $(, $arg)*, 0, $ad,
+                                $crate::aead::Tampering::Ad);
+            aead_counter_bench!(bad_tag, $crate::aead::Counter::Ad,
+                                super::super::$f $(, $arg)*, 0, $ad,
+                                $crate::aead::Tampering::Tag);
+        }
+    };
+    ( $scenario:ident, $chunk_len:expr, $ad:expr,
+      $f:ident $(, $arg:expr)* ) => {
+        mod $scenario {
+            use test;
+
+            mod valid {
+                use test;
+
+                aead_counter_benches!(super::super::super::$f $(, $arg)*,
+                                      $chunk_len, $ad,
+                                      $crate::aead::Tampering::None);
+            }
+
+            aead_counter_bench!(bad_ad, $crate::aead::Counter::Plaintext,
+                                super::super::$f $(, $arg)*, $chunk_len, $ad,
+                                $crate::aead::Tampering::Ad);
+            aead_counter_bench!(bad_body, $crate::aead::Counter::Plaintext,
+                                super::super::$f $(, $arg)*, $chunk_len, $ad,
+                                $crate::aead::Tampering::Body);
+            aead_counter_bench!(bad_tag, $crate::aead::Counter::Plaintext,
+                                super::super::$f $(, $arg)*, $chunk_len, $ad,
+                                $crate::aead::Tampering::Tag);
+        }
+    }
+}
+
+// For benchmark harnesses that register benchmarks at run time, like
+// Criterion: calls `$f` with the `$arg`s followed by the scenario's name,
+// plaintext length, and additional data. It is used as the `$bench` of
+// `aead_scenarios`, `ad_only_aead_scenarios`, or
+// `long_message_aead_scenarios` within a function body.
+#[macro_export]
+macro_rules! aead_scenario_call {
+    ( $scenario:ident, $chunk_len:expr, $ad:expr,
+      $f:ident $(, $arg:expr)* ) => {
+        $f($($arg, )* stringify!($scenario), $chunk_len, $ad);
+    }
+}
+
+// `$bench` is `aead_seal_in_place_bench`, `aead_open_in_place_bench`, or
+// `aead_scenario_call`; it is invoked with each scenario's name, plaintext
+// length, and additional data, followed by `$args`.
+#[macro_export]
+macro_rules! aead_scenarios {
+    ( $bench:ident, $($args:tt)* ) => {
+        // A TLS 1.2 finished message.
+        $bench!(tls12_finished, $crate::aead::TLS12_FINISHED_LEN,
+                &$crate::aead::TLS12_AD, $($args)*);
+        $bench!(tls13_finished, $crate::aead::TLS13_FINISHED_LEN,
+                &$crate::aead::TLS13_AD, $($args)*);
+
+        // For comparison with BoringSSL.
+        $bench!(tls12_16, 16, &$crate::aead::TLS12_AD, $($args)*);
+
+        // ~1 packet of data in TLS.
+        $bench!(tls12_1350, 1350, &$crate::aead::TLS12_AD, $($args)*);
+        $bench!(tls13_1350, 1350, &$crate::aead::TLS13_AD, $($args)*);
+
+        // For comparison with BoringSSL.
+        $bench!(tls12_8192, 8192, &$crate::aead::TLS12_AD, $($args)*);
+        $bench!(tls13_8192, 8192, &$crate::aead::TLS13_AD, $($args)*);
+    }
+}
+
+// The TLS scenarios plus the cost of authenticating additional data alone.
+#[macro_export]
+macro_rules! ad_only_aead_scenarios {
+    ( $bench:ident, $($args:tt)* ) => {
+        aead_scenarios!($bench, $($args)*);
+
+        $bench!(ad_only_1024, 0, &$crate::aead::LARGE_AD[..1024], $($args)*);
+        $bench!(ad_only_8192, 0, &$crate::aead::LARGE_AD, $($args)*);
+    }
+}
+
+// The TLS scenarios plus large messages, where the cost of a second pass over
+// the data, as in AES-SIV and AES-GCM-SIV, matters most. OpenSSL's AES-SIV
+// can't seal an empty plaintext, so there are no `ad_only` scenarios.
+#[macro_export]
+macro_rules! long_message_aead_scenarios {
+    ( $bench:ident, $($args:tt)* ) => {
+        aead_scenarios!($bench, $($args)*);
+
+        $bench!(msg_64kib, 64 * 1024, &$crate::aead::TLS13_AD, $($args)*);
+        $bench!(msg_1mib, 1024 * 1024, &$crate::aead::TLS13_AD, $($args)*);
+    }
+}
+
+#[macro_export]
+macro_rules! aead_benches {
+    ( $name:ident, $bench:ident, $($args:tt)* ) => {
+        mod $name {
+            use test;
+
+            ad_only_aead_scenarios!($bench, $($args)*);
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! long_message_aead_benches {
+    ( $name:ident, $bench:ident, $($args:tt)* ) => {
+        mod $name {
+            use test;
+
+            long_message_aead_scenarios!($bench, $($args)*);
+        }
+    }
+}
+
+// Like `aead_seal_in_place_bench`, but each iteration seals the next record
+// of a connection, with the nonce and additional data constructed as TLS does
+// by `aead::Records`, instead of reusing the same nonce. `$f` is called with
+// the `$arg`s followed by the `aead::TlsVersion`, the plaintext length, the
+// `aead::Counter`, and the `test::Bencher`.
+#[macro_export]
+macro_rules! aead_seal_records_bench {
+    ( $scenario:ident, $version:expr, $chunk_len:expr,
+      $f:ident $(, $arg:expr)* ) => {
+        mod $scenario {
+            use test;
+
+            aead_counter_benches!(super::super::$f $(, $arg)*, $version,
+                                  $chunk_len);
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! aead_seal_records_benches {
+    ( $name:ident, $f:ident $(, $arg:expr)* ) => {
+        mod $name {
+            use test;
+
+            aead_seal_records_bench!(tls12_16, $crate::aead::TlsVersion::Tls12,
+                                     16, $f $(, $arg)*);
+            aead_seal_records_bench!(tls12_1350,
+                                     $crate::aead::TlsVersion::Tls12, 1350,
+                                     $f $(, $arg)*);
+            aead_seal_records_bench!(tls13_1350,
+                                     $crate::aead::TlsVersion::Tls13, 1350,
+                                     $f $(, $arg)*);
+            aead_seal_records_bench!(tls12_8192,
+                                     $crate::aead::TlsVersion::Tls12, 8192,
+                                     $f $(, $arg)*);
+            aead_seal_records_bench!(tls13_8192,
+                                     $crate::aead::TlsVersion::Tls13, 8192,
+                                     $f $(, $arg)*);
+        }
+    }
+}
+
+// A simulation of the TLS record layer, which frames a stream of application
+// data into records and seals them, and opens a stream of records, generic
+// over each implementation's AEAD.
+pub mod record_layer {
+    use aead::{tls12_ad, TlsVersion, TLS_IV};
+
+    // The maximum length of the application data in a record.
+    pub const MAX_FRAGMENT_LEN: usize = 1 << 14;
+
+    // The length of the streams of application data in the benchmarks.
+    pub const STREAM_LEN: usize = 1 << 20;
+
+    const HEADER_LEN: usize = 5;
+    const APPLICATION_DATA: u8 = 23;
+    const TLS12_EXPLICIT_NONCE_LEN: usize = 8;
+
+    // The longest additional data is TLS 1.2's; TLS 1.3's is the header.
+    const MAX_AD_LEN: usize = 13;
+
+    pub trait Aead {
+        fn tag_len(&self) -> usize;
+
+        // `in_out` is the plaintext followed by `tag_len()` bytes of room for
+        // the tag.
+        fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                         in_out: &mut [u8]) -> Result<(), ()>;
+
+        // `in_out` is the ciphertext followed by the tag. On success, the
+        // ciphertext has been replaced with the plaintext.
+        fn open_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                         in_out: &mut [u8]) -> Result<(), ()>;
+    }
+
+    pub struct Config {
+        pub version: TlsVersion,
+
+        // The application data is split into records of at most this many
+        // bytes, which must not exceed `MAX_FRAGMENT_LEN`.
+        pub fragment_len: usize,
+
+        // In TLS 1.3, the content and content type of each record are padded
+        // to a multiple of this many bytes; zero means no padding. Ignored in
+        // TLS 1.2.
+        pub padding: usize,
+    }
+
+    // Frames `data` into records, seals them, and appends them to `out`.
+    // Sequence numbers start at zero, as they do for each new key.
+    pub fn seal<A: Aead>(aead: &mut A, config: &Config, data: &[u8],
+                         out: &mut Vec<u8>) -> Result<(), ()> {
+        let tag_len = aead.tag_len();
+        let mut seq = 0;
+        for fragment in data.chunks(config.fragment_len) {
+            let mut nonce = [0u8; 96 / 8];
+            let mut ad = [0u8; MAX_AD_LEN];
+
+            let header_start = out.len();
+            out.extend_from_slice(&[APPLICATION_DATA, 3, 3, 0, 0]);
+            let body_start;
+            let ad_len;
+            match config.version {
+                TlsVersion::Tls12 => {
+                    tls12_nonce(seq, &mut nonce);
+                    out.extend_from_slice(&nonce[4..]);
+                    body_start = out.len();
+                    out.extend_from_slice(fragment);
+                    ad = tls12_ad(seq, fragment.len());
+                    ad_len = MAX_AD_LEN;
+                },
+                TlsVersion::Tls13 => {
+                    tls13_nonce(seq, &mut nonce);
+                    body_start = out.len();
+                    out.extend_from_slice(fragment);
+                    out.push(APPLICATION_DATA);
+                    let padded_len =
+                        padded_len(fragment.len() + 1, config.padding);
+                    out.resize(body_start + padded_len, 0);
+                    ad_len = HEADER_LEN;
+                },
+            }
+            let body_end = out.len() + tag_len;
+            out.resize(body_end, 0);
+
+            let record_len = body_end - header_start - HEADER_LEN;
+            out[header_start + 3] = (record_len >> 8) as u8;
+            out[header_start + 4] = record_len as u8;
+            if let TlsVersion::Tls13 = config.version {
+                ad[..HEADER_LEN].copy_from_slice(
+                    &out[header_start..(header_start + HEADER_LEN)]);
+            }
+
+            try!(aead.seal_in_place(&nonce, &ad[..ad_len],
+                                    &mut out[body_start..]));
+            seq += 1;
+        }
+        Ok(())
+    }
+
+    // Opens the records in `records` and appends the application data to
+    // `out`.
+    pub fn open<A: Aead>(aead: &mut A, config: &Config, records: &[u8],
+                         out: &mut Vec<u8>) -> Result<(), ()> {
+        let tag_len = aead.tag_len();
+        let mut records = records;
+        let mut seq = 0;
+        while !records.is_empty() {
+            if records.len() < HEADER_LEN {
+                return Err(());
+            }
+            let (header, rest) = records.split_at(HEADER_LEN);
+            if header[0] != APPLICATION_DATA {
+                return Err(());
+            }
+            let record_len = ((header[3] as usize) << 8) | (header[4] as usize);
+            if rest.len() < record_len {
+                return Err(());
+            }
+            let (record, rest) = rest.split_at(record_len);
+            records = rest;
+
+            let mut nonce = [0u8; 96 / 8];
+            let mut ad = [0u8; MAX_AD_LEN];
+            let (body, ad_len) = match config.version {
+                TlsVersion::Tls12 => {
+                    if record_len < TLS12_EXPLICIT_NONCE_LEN + tag_len {
+                        return Err(());
+                    }
+                    let (explicit_nonce, body) =
+                        record.split_at(TLS12_EXPLICIT_NONCE_LEN);
+                    nonce[..4].copy_from_slice(&TLS_IV[..4]);
+                    nonce[4..].copy_from_slice(explicit_nonce);
+                    ad = tls12_ad(seq, body.len() - tag_len);
+                    (body, MAX_AD_LEN)
+                },
+                TlsVersion::Tls13 => {
+                    if record_len < tag_len + 1 {
+                        return Err(());
+                    }
+                    tls13_nonce(seq, &mut nonce);
+                    ad[..HEADER_LEN].copy_from_slice(header);
+                    (record, HEADER_LEN)
+                },
+            };
+
+            // Decrypt in place at the end of `out`, so that the plaintext
+            // doesn't have to be copied again.
+            let start = out.len();
+            out.extend_from_slice(body);
+            try!(aead.open_in_place(&nonce, &ad[..ad_len],
+                                    &mut out[start..]));
+            let mut end = out.len() - tag_len;
+            if let TlsVersion::Tls13 = config.version {
+                // Remove the padding and the content type.
+                loop {
+                    if end == start {
+                        return Err(());
+                    }
+                    end -= 1;
+                    if out[end] != 0 {
+                        break;
+                    }
+                }
+                if out[end] != APPLICATION_DATA {
+                    return Err(());
+                }
+            }
+            out.truncate(end);
+            seq += 1;
+        }
+        Ok(())
+    }
+
+    fn padded_len(len: usize, padding: usize) -> usize {
+        if padding == 0 {
+            return len;
+        }
+        let padded_len = ((len + padding - 1) / padding) * padding;
+        // The content type counts towards the limit, but the padding may not
+        // push the record past it.
+        if padded_len > MAX_FRAGMENT_LEN + 1 { len } else { padded_len }
+    }
+
+    fn sequence_number_bytes(seq: u64) -> [u8; 8] {
+        let mut bytes = [0u8; 8];
+        for i in 0..8 {
+            bytes[i] = (seq >> (56 - (8 * i))) as u8;
+        }
+        bytes
+    }
+
+    // The implicit part of the nonce followed by the explicit part, which is
+    // the sequence number.
+    fn tls12_nonce(seq: u64, nonce: &mut [u8; 96 / 8]) {
+        nonce[..4].copy_from_slice(&TLS_IV[..4]);
+        nonce[4..].copy_from_slice(&sequence_number_bytes(seq));
+    }
+
+    fn tls13_nonce(seq: u64, nonce: &mut [u8; 96 / 8]) {
+        let seq = sequence_number_bytes(seq);
+        *nonce = TLS_IV;
+        for i in 0..8 {
+            nonce[4 + i] ^= seq[i];
+        }
+    }
+}
+
+// `$new_aead` constructs the implementation's `record_layer::Aead`.
+#[macro_export]
+macro_rules! record_layer_seal_bench {
+    ( $bench_fn_name:ident, $version:expr, $fragment_len:expr,
+      $padding:expr, $new_aead:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::record_layer;
+            let config = record_layer::Config {
+                version: $version,
+                fragment_len: $fragment_len,
+                padding: $padding,
+            };
+            let mut aead = $new_aead;
+            let data = vec![0u8; record_layer::STREAM_LEN];
+            let mut records = Vec::new();
+            b.bytes = record_layer::STREAM_LEN as u64;
+            b.iter(|| {
+                records.clear();
+                record_layer::seal(&mut aead, &config, &data, &mut records)
+                    .unwrap();
+            });
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! record_layer_open_bench {
+    ( $bench_fn_name:ident, $version:expr, $fragment_len:expr,
+      $padding:expr, $new_aead:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::record_layer;
+            let config = record_layer::Config {
+                version: $version,
+                fragment_len: $fragment_len,
+                padding: $padding,
+            };
+            let mut aead = $new_aead;
+            let mut records = Vec::new();
+            record_layer::seal(&mut aead, &config,
+                               &vec![0u8; record_layer::STREAM_LEN],
+                               &mut records).unwrap();
+            let mut data = Vec::new();
+            b.bytes = record_layer::STREAM_LEN as u64;
+            b.iter(|| {
+                data.clear();
+                record_layer::open(&mut aead, &config, &records, &mut data)
+                    .unwrap();
+            });
+        }
+    }
+}
+
+// `$bench` is `record_layer_seal_bench` or `record_layer_open_bench`.
+#[macro_export]
+macro_rules! record_layer_benches {
+    ( $bench:ident, $new_aead:expr ) => {
+        use test;
+
+        // Full-sized records, as used for bulk transfers.
+        $bench!(tls12_1mib, $crate::aead::TlsVersion::Tls12,
+                $crate::record_layer::MAX_FRAGMENT_LEN, 0, $new_aead);
+        $bench!(tls13_1mib, $crate::aead::TlsVersion::Tls13,
+                $crate::record_layer::MAX_FRAGMENT_LEN, 0, $new_aead);
+
+        // Small records, as used to reduce latency.
+        $bench!(tls12_1mib_1k_records, $crate::aead::TlsVersion::Tls12,
+                1024, 0, $new_aead);
+        $bench!(tls13_1mib_1k_records, $crate::aead::TlsVersion::Tls13,
+                1024, 0, $new_aead);
+
+        // Records padded to hide their lengths.
+        $bench!(tls13_1mib_1k_records_padded, $crate::aead::TlsVersion::Tls13,
+                1000, 256, $new_aead);
+    }
+}
+
+// STREAM (Hoang, Reyhanitabar, Rogaway, and Vizár, "Online
+// Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance"), which
+// seals a long stream as a sequence of fixed-size segments, generic over each
+// implementation's AEAD. Each segment's nonce is a fixed prefix, a 32-bit
+// big-endian segment counter, and a byte that is 1 only for the last segment,
+// so that reordering and truncation are detected.
+//
+// Streams are processed a segment at a time through a segment-sized buffer,
+// the way a large file is encrypted while it is read, so the plaintext is
+// never held in memory. Only the sealed stream that the `open` benchmarks
+// open is.
+pub mod stream {
+    use record_layer::Aead;
+
+    // In real use, the prefix is random or derived along with the key.
+    pub const NONCE_PREFIX: [u8; 7] = [
+        0x3d, 0x1f, 0x92, 0xa4, 0x07, 0xc5, 0x68
+    ];
+
+    pub struct Config {
+        // The length of the plaintext of the stream.
+        pub len: usize,
+
+        // The length of the plaintext of each segment except the last, which
+        // may be shorter.
+        pub segment_len: usize,
+    }
+
+    impl Config {
+        // An empty stream still has one (empty) segment.
+        pub fn segments(&self) -> usize {
+            if self.len == 0 {
+                1
+            } else {
+                (self.len + self.segment_len - 1) / self.segment_len
+            }
+        }
+
+        pub fn sealed_len(&self, tag_len: usize) -> usize {
+            self.len + (self.segments() * tag_len)
+        }
+    }
+
+    pub fn nonce(counter: usize, last: bool) -> [u8; 96 / 8] {
+        assert!(counter <= 0xffffffff);
+        let mut nonce = [0u8; 96 / 8];
+        nonce[..7].copy_from_slice(&NONCE_PREFIX);
+        nonce[7] = (counter >> 24) as u8;
+        nonce[8] = (counter >> 16) as u8;
+        nonce[9] = (counter >> 8) as u8;
+        nonce[10] = counter as u8;
+        nonce[11] = if last { 1 } else { 0 };
+        nonce
+    }
+
+    // Seals a stream of `config.len` zero bytes, passing each sealed segment to
+    // `write`.
+    pub fn seal<A, W>(aead: &mut A, config: &Config, mut write: W)
+                      -> Result<(), ()> where A: Aead, W: FnMut(&[u8]) {
+        let tag_len = aead.tag_len();
+        let plaintext = vec![0u8; config.segment_len];
+        let mut segment = vec![0u8; config.segment_len + tag_len];
+        let segments = config.segments();
+        let mut remaining = config.len;
+        for counter in 0..segments {
+            let len = if remaining < config.segment_len {
+                remaining
+            } else {
+                config.segment_len
+            };
+            remaining -= len;
+
+            // Read the next segment of the plaintext.
+            let in_out = &mut segment[..(len + tag_len)];
+            in_out[..len].copy_from_slice(&plaintext[..len]);
+
+            try!(aead.seal_in_place(&nonce(counter, counter + 1 == segments),
+                                    &[], in_out));
+            write(in_out);
+        }
+        Ok(())
+    }
+
+    // Opens the sealed stream `sealed`, passing the plaintext of each segment
+    // to `write`.
+    pub fn open<A, W>(aead: &mut A, config: &Config, sealed: &[u8],
+                      mut write: W) -> Result<(), ()>
+                      where A: Aead, W: FnMut(&[u8]) {
+        let tag_len = aead.tag_len();
+        let sealed_segment_len = config.segment_len + tag_len;
+        let mut segment = vec![0u8; sealed_segment_len];
+        let segments = if sealed.is_empty() {
+            return Err(());
+        } else {
+            (sealed.len() + sealed_segment_len - 1) / sealed_segment_len
+        };
+        for (counter, sealed_segment) in
+                sealed.chunks(sealed_segment_len).enumerate() {
+            if sealed_segment.len() < tag_len {
+                return Err(());
+            }
+
+            // Read the next sealed segment.
+            let in_out = &mut segment[..sealed_segment.len()];
+            in_out.copy_from_slice(sealed_segment);
+
+            try!(aead.open_in_place(&nonce(counter, counter + 1 == segments),
+                                    &[], in_out));
+            write(&in_out[..(sealed_segment.len() - tag_len)]);
+        }
+        Ok(())
+    }
+}
+
+// The per-segment overhead: sealing or opening a single empty segment, which
+// costs constructing the nonce, the AEAD's fixed per-message work, and the
+// tag. The sealed empty segment is just the tag, which opening doesn't
+// overwrite, so it doesn't have to be restored.
+#[macro_export]
+macro_rules! stream_seal_bench {
+    ( $bench_fn_name:ident, $new_aead:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::record_layer;
+            use $crate::stream;
+            let mut aead = $new_aead;
+            let tag_len = record_layer::Aead::tag_len(&aead);
+            let mut in_out = vec![0u8; tag_len];
+            b.iter(|| {
+                record_layer::Aead::seal_in_place(
+                    &mut aead, &stream::nonce(0, true), &[], &mut in_out)
+                    .unwrap();
+            });
+        }
+    };
+    ( $(#[$attr:meta])* $bench_fn_name:ident, $len:expr, $segment_len:expr,
+      $new_aead:expr ) => {
+        #[bench]
+        $(#[$attr])*
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::stream;
+            let config = stream::Config {
+                len: $len,
+                segment_len: $segment_len,
+            };
+            let mut aead = $new_aead;
+            b.bytes = config.len as u64;
+            b.iter(|| {
+                stream::seal(&mut aead, &config, |segment| {
+                    test::black_box(segment);
+                }).unwrap();
+            });
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! stream_open_bench {
+    ( $bench_fn_name:ident, $new_aead:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::record_layer;
+            use $crate::stream;
+            let mut aead = $new_aead;
+            let tag_len = record_layer::Aead::tag_len(&aead);
+            let nonce = stream::nonce(0, true);
+            let mut in_out = vec![0u8; tag_len];
+            record_layer::Aead::seal_in_place(&mut aead, &nonce, &[],
+                                              &mut in_out).unwrap();
+            b.iter(|| {
+                record_layer::Aead::open_in_place(&mut aead, &nonce, &[],
+                                                  &mut in_out).unwrap();
+            });
+        }
+    };
+    ( $(#[$attr:meta])* $bench_fn_name:ident, $len:expr, $segment_len:expr,
+      $new_aead:expr ) => {
+        #[bench]
+        $(#[$attr])*
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::stream;
+            let config = stream::Config {
+                len: $len,
+                segment_len: $segment_len,
+            };
+            let mut aead = $new_aead;
+            let tag_len = $crate::record_layer::Aead::tag_len(&aead);
+            let mut sealed = Vec::with_capacity(config.sealed_len(tag_len));
+            stream::seal(&mut aead, &config, |segment| {
+                sealed.extend_from_slice(segment);
+            }).unwrap();
+            b.bytes = config.len as u64;
+            b.iter(|| {
+                stream::open(&mut aead, &config, &sealed, |segment| {
+                    test::black_box(segment);
+                }).unwrap();
+            });
+        }
+    }
+}
+
+// `$bench` is `stream_seal_bench` or `stream_open_bench`.
+//
+// libtest runs each benchmark at least 300 times, so the streams of 64 MiB and
+// more would take hours, and the 1 GiB open benchmark holds the whole sealed
+// stream in memory. They're `#[ignore]`d; run them with `-- --ignored`.
+#[macro_export]
+macro_rules! stream_benches {
+    ( $bench:ident, $new_aead:expr ) => {
+        use test;
+
+        $bench!(segment_overhead, $new_aead);
+
+        $bench!(len_1mib_seg_4kib, 1 << 20, 4 << 10, $new_aead);
+        $bench!(len_1mib_seg_64kib, 1 << 20, 64 << 10, $new_aead);
+        $bench!(len_1mib_seg_1mib, 1 << 20, 1 << 20, $new_aead);
+
+        $bench!(#[ignore] len_64mib_seg_4kib, 64 << 20, 4 << 10, $new_aead);
+        $bench!(#[ignore] len_64mib_seg_64kib, 64 << 20, 64 << 10, $new_aead);
+        $bench!(#[ignore] len_64mib_seg_1mib, 64 << 20, 1 << 20, $new_aead);
+
+        // Sustained throughput, as when sealing multi-gigabyte backups.
+        $bench!(#[ignore] len_1gib_seg_1mib, 1 << 30, 1 << 20, $new_aead);
+    }
+}
+
+// A server with many TLS sessions seals each record with the keys of whichever
+// session it belongs to, so the keys usually aren't in the CPU's caches. These
+// benchmarks seal a small record with a randomly chosen one of many keys. Each
+// key is only a session's sealing key, so that the working set is the one the
+// `key_footprint` tools measure.
+pub mod many_keys {
+    use aead::{NONCE, TLS13_AD};
+
+    pub const RECORD_LEN: usize = 16;
+
+    pub trait SealingKey {
+        fn tag_len(&self) -> usize;
+
+        // `in_out` is the plaintext followed by `tag_len()` bytes of room for
+        // the tag.
+        fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                         in_out: &mut [u8]) -> Result<(), ()>;
+    }
+
+    // A xorshift generator, so that choosing a key is cheap compared to
+    // sealing the record and every implementation chooses the same keys.
+    pub struct KeyChooser {
+        state: u64,
+        key_count: usize,
+    }
+
+    impl KeyChooser {
+        pub fn new(key_count: usize) -> KeyChooser {
+            KeyChooser { state: 0x2545f4914f6cdd1d, key_count: key_count }
+        }
+
+        pub fn advance(&mut self) -> usize {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            (self.state % (self.key_count as u64)) as usize
+        }
+    }
+
+    pub fn seal<K>(keys: &mut [K], chooser: &mut KeyChooser, in_out: &mut [u8])
+                   -> Result<(), ()> where K: SealingKey {
+        keys[chooser.advance()].seal_in_place(&NONCE, &TLS13_AD, in_out)
+    }
+}
+
+#[macro_export]
+macro_rules! many_keys_seal_bench {
+    ( $bench_fn_name:ident, $key_count:expr, $new_key:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::many_keys::{KeyChooser, RECORD_LEN};
+
+            let mut keys =
+                (0..$key_count).map(|_| $new_key).collect::<Vec<_>>();
+            let tag_len = $crate::many_keys::SealingKey::tag_len(&keys[0]);
+            let mut chooser = KeyChooser::new(keys.len());
+            let mut in_out = vec![0u8; RECORD_LEN + tag_len];
+            b.bytes = RECORD_LEN as u64;
+            b.iter(|| {
+                $crate::many_keys::seal(&mut keys, &mut chooser, &mut in_out)
+                    .unwrap();
+            });
+        }
+    }
+}
+
+// Comparing the key counts shows how the throughput degrades as the keys
+// stop fitting in each level of the cache.
+#[macro_export]
+macro_rules! many_keys_benches {
+    ( $new_key:expr ) => {
+        use test;
+
+        many_keys_seal_bench!(keys_1, 1, $new_key);
+        many_keys_seal_bench!(keys_10, 10, $new_key);
+        many_keys_seal_bench!(keys_100, 100, $new_key);
+        many_keys_seal_bench!(keys_1000, 1000, $new_key);
+        many_keys_seal_bench!(keys_10000, 10000, $new_key);
+        many_keys_seal_bench!(keys_100000, 100000, $new_key);
+    }
+}
+
+// Reports how much memory each of an implementation's keys takes, for the
+// `key_footprint` tools.
+pub mod footprint {
+    use std;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Counts the bytes that are allocated through Rust's allocator. Memory
+    // that C code allocates with `malloc` isn't counted.
+    pub struct CountingAllocator;
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    const COUNT: usize = 1000;
+
+    // Prints the size of a `T` made by `new` and the heap memory it owns,
+    // averaged over `COUNT` of them. `CountingAllocator` must be the global
+    // allocator.
+    pub fn report<T, F>(implementation: &str, name: &str, mut new: F)
+                        where F: FnMut() -> T {
+        let mut values = Vec::with_capacity(COUNT);
+        let before = ALLOCATED.load(Ordering::SeqCst);
+        for _ in 0..COUNT {
+            values.push(new());
+        }
+        let heap_len = ALLOCATED.load(Ordering::SeqCst) - before;
+        println!("{}: {}: {} bytes, plus {} bytes on the heap", implementation,
+                 name, std::mem::size_of::<T>(), heap_len / COUNT);
+    }
+
+    const RESIDENT_COUNT: usize = 10000;
+
+    // Like `report`, but for a `T` whose memory is allocated by C code, which
+    // `CountingAllocator` can't see: prints how much the process's resident
+    // set grows per `T`, averaged over `RESIDENT_COUNT` of them. Only works
+    // on Linux.
+    pub fn report_resident<T, F>(implementation: &str, name: &str, mut new: F)
+                                 where F: FnMut() -> T {
+        // The first one may also initialize the library, which isn't part of
+        // the cost of a key.
+        let _first = new();
+        let mut values = Vec::with_capacity(RESIDENT_COUNT);
+        let before = ::peak_memory::status_kib("VmRSS:");
+        for _ in 0..RESIDENT_COUNT {
+            values.push(new());
+        }
+        let after = ::peak_memory::status_kib("VmRSS:");
+        match (before, after) {
+            (Some(before), Some(after)) =>
+                println!("{}: {}: {} bytes resident", implementation, name,
+                         (after.saturating_sub(before) * 1024) /
+                             RESIDENT_COUNT),
+            _ =>
+                println!("{}: {}: unknown (only measured on Linux)",
+                         implementation, name),
+        }
+    }
+}
+
+// The MAC-then-encrypt record protection of the TLS 1.2 AES-CBC cipher
+// suites (RFC 5246 Section 6.2.3.2), generic over each implementation's AES-CBC
+// and HMAC.
+pub mod cbc_hmac {
+    pub const BLOCK_LEN: usize = 128 / 8;
+
+    // Real implementations generate a random IV for every record; that cost
+    // isn't included.
+    pub const IV: [u8; BLOCK_LEN] = [
+        0x3d, 0x6a, 0x1f, 0x72, 0x0b, 0x9c, 0xe4, 0x58,
+        0x81, 0x27, 0xc6, 0x40, 0xf3, 0x15, 0xaa, 0x6e,
+    ];
+
+    const MAX_MAC_LEN: usize = 512 / 8;
+
+    pub trait CbcHmac {
+        fn mac_len(&self) -> usize;
+
+        // Writes HMAC(mac_key, parts[0] || parts[1] || ...) into `out`, which
+        // is `mac_len()` bytes long.
+        fn sign(&mut self, parts: &[&[u8]], out: &mut [u8]);
+
+        // Encrypts or decrypts the first `len` bytes of `in_out` in place,
+        // without padding. `len` is a multiple of `BLOCK_LEN`. `in_out` is
+        // `BLOCK_LEN` bytes longer than `len` because some implementations
+        // require room for an extra block of output.
+        fn encrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+                   len: usize);
+        fn decrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+                   len: usize);
+    }
+
+    // Appends IV || E(plaintext || MAC || padding || padding_length) to
+    // `out`, where MAC = HMAC(ad || plaintext).
+    pub fn seal<C: CbcHmac>(c: &mut C, ad: &[u8], plaintext: &[u8],
+                            out: &mut Vec<u8>) {
+        let mac_len = c.mac_len();
+        out.extend_from_slice(&IV);
+        let body_start = out.len();
+        out.extend_from_slice(plaintext);
+
+        let mac_start = out.len();
+        out.resize(mac_start + mac_len, 0);
+        c.sign(&[ad, plaintext], &mut out[mac_start..]);
+
+        let unpadded_len = plaintext.len() + mac_len + 1;
+        let padding_len = (BLOCK_LEN - (unpadded_len % BLOCK_LEN)) % BLOCK_LEN;
+        for _ in 0..(padding_len + 1) {
+            out.push(padding_len as u8);
+        }
+
+        let body_len = out.len() - body_start;
+        out.resize(body_start + body_len + BLOCK_LEN, 0);
+        c.encrypt(&IV, &mut out[body_start..], body_len);
+        out.truncate(body_start + body_len);
+    }
+
+    // Decrypts `record`, checks its padding and MAC, and appends the
+    // plaintext to `out`.
+    //
+    // Real implementations also take care to make the padding and MAC checks
+    // take the same time whether or not the padding is valid ("Lucky
+    // Thirteen"), which costs more.
+    pub fn open<C: CbcHmac>(c: &mut C, ad: &[u8], record: &[u8],
+                            out: &mut Vec<u8>) -> Result<(), ()> {
+        let mac_len = c.mac_len();
+        if record.len() < BLOCK_LEN {
+            return Err(());
+        }
+        let (iv, body) = record.split_at(BLOCK_LEN);
+        let body_len = body.len();
+        if body_len % BLOCK_LEN != 0 || body_len < mac_len + 1 {
+            return Err(());
+        }
+        let mut iv_bytes = [0u8; BLOCK_LEN];
+        iv_bytes.copy_from_slice(iv);
+
+        // Decrypt in place at the end of `out`, so that the plaintext doesn't
+        // have to be copied again.
+        let start = out.len();
+        out.extend_from_slice(body);
+        out.resize(start + body_len + BLOCK_LEN, 0);
+        c.decrypt(&iv_bytes, &mut out[start..], body_len);
+        out.truncate(start + body_len);
+
+        let padding_len = out[out.len() - 1] as usize;
+        if padding_len + 1 + mac_len > body_len {
+            return Err(());
+        }
+        let mac_end = out.len() - padding_len - 1;
+        let mut bad = 0;
+        for b in &out[mac_end..] {
+            bad |= *b ^ (padding_len as u8);
+        }
+
+        let plaintext_end = mac_end - mac_len;
+        let mut mac = [0u8; MAX_MAC_LEN];
+        c.sign(&[ad, &out[start..plaintext_end]], &mut mac[..mac_len]);
+        for (a, b) in mac[..mac_len].iter().zip(&out[plaintext_end..mac_end]) {
+            bad |= a ^ b;
+        }
+        if bad != 0 {
+            return Err(());
+        }
+        out.truncate(plaintext_end);
+        Ok(())
+    }
+}
+
+// `$new_cbc_hmac` constructs the implementation's `cbc_hmac::CbcHmac`.
+#[macro_export]
+macro_rules! cbc_hmac_seal_bench {
+    ( $bench_fn_name:ident, $chunk_len:expr, $new_cbc_hmac:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::cbc_hmac;
+            let mut c = $new_cbc_hmac;
+            let plaintext = vec![0u8; $chunk_len];
+            let mut record = Vec::new();
+            b.bytes = $chunk_len as u64;
+            b.iter(|| {
+                record.clear();
+                cbc_hmac::seal(&mut c, &$crate::aead::TLS12_AD, &plaintext,
+                               &mut record);
+            });
+        }
+    }
+}
 
-    // In TLS 1.2, 13 bytes of additional data are used for AEAD cipher suites.
-    pub const TLS12_AD: [u8; 13] = [
-        23,         // Type: application_data
-        3, 3,       // Version = TLS 1.2.
-        0x12, 0x34, // Length = 0x1234.
-        0, 0, 0, 0, 0, 0, 0, 1, // Record #1
-    ];
+#[macro_export]
+macro_rules! cbc_hmac_open_bench {
+    ( $bench_fn_name:ident, $chunk_len:expr, $new_cbc_hmac:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::cbc_hmac;
+            let mut c = $new_cbc_hmac;
+            let mut record = Vec::new();
+            cbc_hmac::seal(&mut c, &$crate::aead::TLS12_AD,
+                           &vec![0u8; $chunk_len], &mut record);
+            let mut plaintext = Vec::new();
+            b.bytes = $chunk_len as u64;
+            b.iter(|| {
+                plaintext.clear();
+                cbc_hmac::open(&mut c, &$crate::aead::TLS12_AD, &record,
+                               &mut plaintext).unwrap();
+            });
+        }
+    }
+}
 
-    // In TLS 1.3, no additional data is used for AEAD cipher suites.
-    pub const TLS13_AD: [u8; 0] = [ ];
+// `$bench` is `cbc_hmac_seal_bench` or `cbc_hmac_open_bench`. The record
+// sizes are the TLS 1.2 ones of the AEAD benchmarks.
+#[macro_export]
+macro_rules! cbc_hmac_benches {
+    ( $bench:ident, $new_cbc_hmac:expr ) => {
+        use test;
+
+        $bench!(tls12_finished, $crate::aead::TLS12_FINISHED_LEN,
+                $new_cbc_hmac);
+        $bench!(tls12_16, 16, $new_cbc_hmac);
+        $bench!(tls12_1350, 1350, $new_cbc_hmac);
+        $bench!(tls12_8192, 8192, $new_cbc_hmac);
+    }
+}
+
+// Raw AES, without a mode of operation, so that the cost of AES itself can be
+// separated from the cost of GHASH in AES-GCM. Bulk encryption is ECB, i.e.
+// each block is encrypted independently.
+pub mod block_cipher {
+    pub const BLOCK_LEN: usize = 128 / 8;
+
+    pub trait BlockCipher {
+        // `len` is a multiple of `BLOCK_LEN`, and `in_out` has room for
+        // another `BLOCK_LEN` bytes after the first `len` bytes, which some
+        // implementations require even without padding.
+        fn encrypt(&mut self, in_out: &mut [u8], len: usize);
+        fn decrypt(&mut self, in_out: &mut [u8], len: usize);
+    }
+}
+
+#[macro_export]
+macro_rules! block_cipher_encrypt_bench {
+    ( $bench_fn_name:ident, $len:expr, $new_block_cipher:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::block_cipher;
+            let mut c = $new_block_cipher;
+            let mut in_out = vec![0u8; $len + block_cipher::BLOCK_LEN];
+            b.bytes = $len as u64;
+            b.iter(|| {
+                block_cipher::BlockCipher::encrypt(&mut c, &mut in_out, $len)
+            });
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! block_cipher_decrypt_bench {
+    ( $bench_fn_name:ident, $len:expr, $new_block_cipher:expr ) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            use $crate::block_cipher;
+            let mut c = $new_block_cipher;
+            let mut in_out = vec![0u8; $len + block_cipher::BLOCK_LEN];
+            b.bytes = $len as u64;
+            b.iter(|| {
+                block_cipher::BlockCipher::decrypt(&mut c, &mut in_out, $len)
+            });
+        }
+    }
+}
+
+// `$bench` is `block_cipher_encrypt_bench` or `block_cipher_decrypt_bench`.
+#[macro_export]
+macro_rules! block_cipher_benches {
+    ( $bench:ident, $new_block_cipher:expr ) => {
+        use test;
+
+        $bench!(block, $crate::block_cipher::BLOCK_LEN, $new_block_cipher);
+        $bench!(ecb_1024, 1024, $new_block_cipher);
+        $bench!(ecb_8192, 8192, $new_block_cipher); // For comparison with GCM.
+    }
+}
+
+// The plaintext lengths of the AEAD benchmarks, for benchmarking the stream
+// ciphers and MACs that AEADs are built from on their own, so that the AEAD
+// timings can be decomposed. `$bench` takes the name of the benchmark and the
+// input length, followed by the `$arg`s.
+#[macro_export]
+macro_rules! aead_part_benches {
+    ( $bench:ident $(, $arg:expr)* ) => {
+        use test;
+
+        $bench!(_12, $crate::aead::TLS12_FINISHED_LEN $(, $arg)*);
+        $bench!(_16, 16 $(, $arg)*);
+        $bench!(_32, $crate::aead::TLS13_FINISHED_LEN $(, $arg)*);
+        $bench!(_1350, 1350 $(, $arg)*);
+        $bench!(_8192, 8192 $(, $arg)*);
+    }
+}
+
+// Bulk encryption lengths, for the unauthenticated modes that are used for
+// large, seekable data. `$bench` is called like it is in `aead_part_benches`.
+#[macro_export]
+macro_rules! bulk_benches {
+    ( $bench:ident $(, $arg:expr)* ) => {
+        use test;
+
+        $bench!(_8192, 8192 $(, $arg)*);
+        $bench!(_64kib, 64 * 1024 $(, $arg)*);
+        $bench!(_1mib, 1024 * 1024 $(, $arg)*);
+    }
+}
+
+// AES-XTS encrypts each disk sector separately, with the sector number as the
+// tweak, so every sector pays for encrypting a new tweak.
+pub mod xts {
+    pub const TWEAK_LEN: usize = 128 / 8;
+
+    // The sector number, little-endian, as in IEEE 1619 and dm-crypt's
+    // `plain64` IVs.
+    pub fn tweak(sector: u64) -> [u8; TWEAK_LEN] {
+        let mut tweak = [0u8; TWEAK_LEN];
+        for (i, byte) in tweak.iter_mut().take(8).enumerate() {
+            *byte = (sector >> (8 * i)) as u8;
+        }
+        tweak
+    }
+}
+
+// `$bench` takes the name of the benchmark, the total length, and the sector
+// length, followed by the `$arg`s. A single sector shows the per-sector
+// overhead; 1 MiB of sectors shows the throughput of a larger write.
+#[macro_export]
+macro_rules! xts_benches {
+    ( $bench:ident $(, $arg:expr)* ) => {
+        use test;
+
+        $bench!(sector_512, 512, 512 $(, $arg)*);
+        $bench!(sector_4096, 4096, 4096 $(, $arg)*);
+        $bench!(len_1mib_sector_512, 1024 * 1024, 512 $(, $arg)*);
+        $bench!(len_1mib_sector_4096, 1024 * 1024, 4096 $(, $arg)*);
+    }
+}
+
+// AES key wrap (RFC 3394) and AES key wrap with padding (RFC 5649).
+pub mod key_wrap {
+    // The integrity check value that is prepended to the wrapped key.
+    pub const ICV_LEN: usize = 64 / 8;
+}
+
+// The lengths of the wrapped keys: AES-128 and AES-256 data keys, and a pair
+// of 256-bit keys. `$bench` is called like it is in `aead_part_benches`.
+#[macro_export]
+macro_rules! key_wrap_benches {
+    ( $bench:ident $(, $arg:expr)* ) => {
+        use test;
+
+        $bench!(key_16, 16 $(, $arg)*);
+        $bench!(key_32, 32 $(, $arg)*);
+        $bench!(key_64, 64 $(, $arg)*);
+    }
+}
+
+pub mod poly1305 {
+    pub const KEY_LEN: usize = 256 / 8;
+    pub const TAG_LEN: usize = 128 / 8;
+
+    // The incremental benchmarks input the data in pieces of this length, a
+    // ChaCha20 block, as when the ciphertext is authenticated while it's being
+    // encrypted.
+    pub const UPDATE_LEN: usize = 64;
 }
 
 pub const SHA1_BLOCK_LEN: usize = 512 / 8;
@@ -62,6 +1544,40 @@ macro_rules! digest_benches {
     }
 }
 
+// Like `digest_bench`, except `$key` is set up with `$new_key` before the
+// timing starts, so that only `$calculation` over `$input` is measured.
+#[macro_export]
+macro_rules! mac_bench {
+    ( $bench_fn_name:ident, $input_len:expr, $key:ident, $new_key:expr,
+      $input:ident, $calculation:expr) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            #[allow(unused_mut)]
+            let mut $key = $new_key;
+            let $input = vec![0u8; $input_len];
+            let $input = &$input[..];
+            b.bytes = $input_len as u64;
+            b.iter(|| $calculation);
+        }
+    }
+}
+
+// The input lengths of `digest_benches`. All of the MACs have a 16 byte
+// block, which is the same as `_16`, so there's no `block_len`.
+#[macro_export]
+macro_rules! mac_benches {
+    ($key:ident, $new_key:expr, $input:ident, $calculation:expr) =>
+    {
+        use test;
+
+        mac_bench!(_16, 16, $key, $new_key, $input, $calculation);
+        mac_bench!(_256, 256, $key, $new_key, $input, $calculation);
+        mac_bench!(_1000, 1000, $key, $new_key, $input, $calculation);
+        mac_bench!(_2000, 2000, $key, $new_key, $input, $calculation);
+        mac_bench!(_8192, 8192, $key, $new_key, $input, $calculation);
+    }
+}
+
 pub mod pbkdf2 {
     // These values are copied from
     // https://github.com/ctz/rust-fastpbkdf2/tree/master/pbkdf2-bench, except
@@ -70,8 +1586,69 @@ pub mod pbkdf2 {
     // realistic for most applications anyway.
     pub const ITERATIONS: u32 = 100_000;
     pub const PASSWORD: &'static [u8] = b"password";
-    pub const PASSWORD_STR: &'static str = "password";
     pub const SALT: &'static [u8] = b"salt";
+
+    pub mod calibrate {
+        use std;
+        use std::time::{Duration, Instant};
+
+        // The target time is the first command line argument, in
+        // milliseconds, defaulting to 100ms.
+        pub fn target_from_args() -> Duration {
+            let millis = match std::env::args().nth(1) {
+                Some(arg) => arg.parse().expect("target must be a number of \
+                                                 milliseconds"),
+                None => 100,
+            };
+            Duration::from_millis(millis)
+        }
+
+        // Finds the number of iterations for which `derive(iterations, out)`
+        // takes about `target` to compute. An upper bound is found by
+        // doubling the iteration count and then the iteration count is
+        // narrowed down to within 1% by binary search.
+        pub fn calibrate<F>(target: Duration, out_len: usize, mut derive: F)
+                            -> u32 where F: FnMut(u32, &mut [u8]) {
+            let mut out = vec![0u8; out_len];
+            let mut takes_less_than_target = |iterations: u32| {
+                let start = Instant::now();
+                derive(iterations, &mut out);
+                start.elapsed() < target
+            };
+
+            let mut lo = 1;
+            let mut hi = 1;
+            while takes_less_than_target(hi) {
+                lo = hi;
+                hi *= 2;
+            }
+
+            while hi - lo > 1 + lo / 100 {
+                let mid = lo + (hi - lo) / 2;
+                if takes_less_than_target(mid) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        }
+
+        // Calibrates `derive` and prints the result as a
+        // "<prf> <implementation> <iterations>" line. The `calibrate_pbkdf2`
+        // script combines these lines from every implementation into a
+        // table.
+        pub fn report<F>(implementation: &str, prf: &str, target: Duration,
+                         out_len: usize, derive: F)
+                         where F: FnMut(u32, &mut [u8]) {
+            let iterations = calibrate(target, out_len, derive);
+            println!("{} {} {}", prf, implementation, iterations);
+        }
+
+        pub fn report_unsupported(implementation: &str, prf: &str) {
+            println!("{} {} unsupported", prf, implementation);
+        }
+    }
 }
 
 #[macro_export]
@@ -84,3 +1661,455 @@ macro_rules! pbkdf2_bench {
         }
     }
 }
+
+#[macro_export]
+macro_rules! pbkdf2_benches {
+    ( $output_len:expr, $out:ident, $calculation:expr) => {
+        use test;
+
+        // PBKDF2 computes each `$output_len`-byte block of its output
+        // independently, so asking for more output multiplies the work. It is
+        // common to derive more than one block, e.g. an encryption key and a
+        // MAC key from PBKDF2-HMAC-SHA256.
+        pbkdf2_bench!(_1x, 1 * $output_len, $out, $calculation);
+        pbkdf2_bench!(_2x, 2 * $output_len, $out, $calculation);
+        pbkdf2_bench!(_4x, 4 * $output_len, $out, $calculation);
+        pbkdf2_bench!(_8x, 8 * $output_len, $out, $calculation);
+    }
+}
+
+pub mod password_hash {
+    pub const PASSWORD: &'static [u8] = b"password";
+
+    // bcrypt requires a 16-byte salt, and Argon2 requires at least 8 bytes.
+    pub const SALT: &'static [u8] = b"saltsaltsaltsalt";
+
+    pub const OUTPUT_LEN: usize = 256 / 8;
+
+    // bcrypt's output is always 192 bits.
+    pub const BCRYPT_OUTPUT_LEN: usize = 192 / 8;
+}
+
+// The names of the password hashing benchmarks end with the amount of memory
+// that the function's parameters nominally require, which is what makes these
+// functions "memory-hard." The `password_hash_memory` tools measure how much
+// they really use, with `peak_memory`.
+
+// Measures the peak memory use of a single call, as the growth of the
+// process's peak resident set size (`VmHWM`), which Linux lets a process
+// reset. Unlike `footprint::CountingAllocator`, this also counts memory that C
+// code allocates, and the stack.
+pub mod peak_memory {
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::process::{self, Command};
+
+    // Names the one call that a child process measures.
+    const CHILD_VAR: &'static str = "CRYPTO_BENCH_PEAK_MEMORY_OF";
+
+    pub fn status_kib(field: &str) -> Option<usize> {
+        let status = match fs::read_to_string("/proc/self/status") {
+            Ok(status) => status,
+            Err(_) => { return None; },
+        };
+        status.lines()
+              .filter(|line| line.starts_with(field))
+              .filter_map(|line| {
+                  line[field.len()..].trim().trim_end_matches("kB").trim()
+                      .parse().ok()
+              })
+              .next()
+    }
+
+    fn measure<F>(f: F) -> Option<usize> where F: FnOnce() {
+        // Writing "5" to `clear_refs` resets `VmHWM` to the current RSS.
+        let reset = fs::OpenOptions::new().write(true)
+            .open("/proc/self/clear_refs")
+            .and_then(|mut clear_refs| clear_refs.write_all(b"5"));
+        if reset.is_err() {
+            return None;
+        }
+        let before = match status_kib("VmRSS:") {
+            Some(before) => before,
+            None => { return None; },
+        };
+        f();
+        status_kib("VmHWM:").map(|peak| peak.saturating_sub(before))
+    }
+
+    // Prints the peak memory use of `f`. Memory that earlier calls freed may
+    // still be resident, so each call is measured in a new process, which
+    // runs the program again up to the call named `name` and then exits.
+    pub fn report<F>(implementation: &str, name: &str, f: F)
+                     where F: FnOnce() {
+        match env::var(CHILD_VAR) {
+            Ok(ref child_name) if child_name == name => {
+                match measure(f) {
+                    Some(kib) =>
+                        println!("{}: {}: {} KiB", implementation, name, kib),
+                    None =>
+                        println!("{}: {}: unknown (only measured on Linux)",
+                                 implementation, name),
+                }
+                process::exit(0);
+            },
+            Ok(_) => {},
+            Err(_) => {
+                let status = Command::new(env::current_exe().unwrap())
+                    .env(CHILD_VAR, name)
+                    .status()
+                    .unwrap();
+                assert!(status.success());
+            },
+        }
+    }
+}
+
+// The parameters of the scrypt benchmarks. `$m` is invoked with each set's
+// name and `log_n`, `r`, and `p`, followed by `$args`.
+#[macro_export]
+macro_rules! scrypt_params {
+    ( $m:ident, $($args:tt)* ) => {
+        // scrypt uses 128 * r * N bytes of memory. The `p` computations are
+        // independent and done sequentially by the implementations we test,
+        // so they reuse the same memory.
+        $m!(log_n_10_r_8_p_1_1mib, 10, 8, 1, $($args)*);
+        // The parameters recommended for interactive logins in the scrypt
+        // paper.
+        $m!(log_n_14_r_8_p_1_16mib, 14, 8, 1, $($args)*);
+        $m!(log_n_14_r_8_p_2_16mib, 14, 8, 2, $($args)*);
+        $m!(log_n_13_r_16_p_1_16mib, 13, 16, 1, $($args)*);
+        $m!(log_n_15_r_8_p_1_32mib, 15, 8, 1, $($args)*);
+    }
+}
+
+#[macro_export]
+macro_rules! scrypt_bench {
+    ( $bench_fn_name:ident, $log_n_value:expr, $r_value:expr, $p_value:expr,
+      $log_n:ident, $r:ident, $p:ident, $out:ident, $calculation:expr) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            let $log_n: u8 = $log_n_value;
+            let $r: u32 = $r_value;
+            let $p: u32 = $p_value;
+            let mut $out = [0u8; $crate::password_hash::OUTPUT_LEN];
+            b.iter(|| $calculation)
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! scrypt_benches {
+    ( $log_n:ident, $r:ident, $p:ident, $out:ident, $calculation:expr) => {
+        use test;
+
+        scrypt_params!(scrypt_bench, $log_n, $r, $p, $out, $calculation);
+    }
+}
+
+#[macro_export]
+macro_rules! scrypt_peak_memory {
+    ( $name:ident, $log_n_value:expr, $r_value:expr, $p_value:expr,
+      $implementation:expr, $log_n:ident, $r:ident, $p:ident, $out:ident,
+      $calculation:expr) => {
+        {
+            let $log_n: u8 = $log_n_value;
+            let $r: u32 = $r_value;
+            let $p: u32 = $p_value;
+            let mut $out = [0u8; $crate::password_hash::OUTPUT_LEN];
+            $crate::peak_memory::report(
+                $implementation,
+                concat!("password_hash::scrypt::", stringify!($name)),
+                || { $calculation; });
+        }
+    }
+}
+
+// The parameters of the bcrypt benchmarks. bcrypt always uses ~4KiB of memory
+// for its Blowfish state.
+#[macro_export]
+macro_rules! bcrypt_params {
+    ( $m:ident, $($args:tt)* ) => {
+        $m!(cost_8_4kib, 8, $($args)*);
+        $m!(cost_10_4kib, 10, $($args)*);
+        $m!(cost_12_4kib, 12, $($args)*);
+    }
+}
+
+#[macro_export]
+macro_rules! bcrypt_bench {
+    ( $bench_fn_name:ident, $cost_value:expr, $cost:ident, $out:ident,
+      $calculation:expr) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            let $cost: u32 = $cost_value;
+            let mut $out = [0u8; $crate::password_hash::BCRYPT_OUTPUT_LEN];
+            b.iter(|| $calculation)
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! bcrypt_benches {
+    ( $cost:ident, $out:ident, $calculation:expr) => {
+        use test;
+
+        bcrypt_params!(bcrypt_bench, $cost, $out, $calculation);
+    }
+}
+
+#[macro_export]
+macro_rules! bcrypt_peak_memory {
+    ( $name:ident, $cost_value:expr, $implementation:expr, $cost:ident,
+      $out:ident, $calculation:expr) => {
+        {
+            let $cost: u32 = $cost_value;
+            let mut $out = [0u8; $crate::password_hash::BCRYPT_OUTPUT_LEN];
+            $crate::peak_memory::report(
+                $implementation,
+                concat!("password_hash::bcrypt::", stringify!($name)),
+                || { $calculation; });
+        }
+    }
+}
+
+// The parameters of the Argon2id benchmarks. Argon2 uses `m_cost` KiB of
+// memory.
+#[macro_export]
+macro_rules! argon2id_params {
+    ( $m:ident, $($args:tt)* ) => {
+        $m!(m_4mib_t_3_p_1, 4 * 1024, 3, 1, $($args)*);
+        // The parameters recommended by OWASP.
+        $m!(m_19mib_t_2_p_1, 19 * 1024, 2, 1, $($args)*);
+        $m!(m_64mib_t_1_p_1, 64 * 1024, 1, 1, $($args)*);
+        $m!(m_64mib_t_3_p_1, 64 * 1024, 3, 1, $($args)*);
+    }
+}
+
+#[macro_export]
+macro_rules! argon2id_bench {
+    ( $bench_fn_name:ident, $m_cost_value:expr, $t_cost_value:expr,
+      $p_cost_value:expr, $m_cost:ident, $t_cost:ident, $p_cost:ident,
+      $out:ident, $calculation:expr) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            let $m_cost: u32 = $m_cost_value;
+            let $t_cost: u32 = $t_cost_value;
+            let $p_cost: u32 = $p_cost_value;
+            let mut $out = [0u8; $crate::password_hash::OUTPUT_LEN];
+            b.iter(|| $calculation)
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! argon2id_benches {
+    ( $m_cost:ident, $t_cost:ident, $p_cost:ident, $out:ident,
+      $calculation:expr) => {
+        use test;
+
+        argon2id_params!(argon2id_bench, $m_cost, $t_cost, $p_cost, $out,
+                         $calculation);
+    }
+}
+
+#[macro_export]
+macro_rules! argon2id_peak_memory {
+    ( $name:ident, $m_cost_value:expr, $t_cost_value:expr,
+      $p_cost_value:expr, $implementation:expr, $m_cost:ident,
+      $t_cost:ident, $p_cost:ident, $out:ident, $calculation:expr) => {
+        {
+            let $m_cost: u32 = $m_cost_value;
+            let $t_cost: u32 = $t_cost_value;
+            let $p_cost: u32 = $p_cost_value;
+            let mut $out = [0u8; $crate::password_hash::OUTPUT_LEN];
+            $crate::peak_memory::report(
+                $implementation,
+                concat!("password_hash::argon2id::", stringify!($name)),
+                || { $calculation; });
+        }
+    }
+}
+
+pub mod tls12_prf {
+    pub const MASTER_SECRET_LABEL: &'static [u8] = b"master secret";
+    pub const KEY_EXPANSION_LABEL: &'static [u8] = b"key expansion";
+
+    pub const MASTER_SECRET_LEN: usize = 48;
+
+    // The premaster secret of ECDHE key exchange with P-256 or X25519.
+    pub const PREMASTER_SECRET_LEN: usize = 32;
+
+    // ClientHello.random + ServerHello.random, or ServerHello.random +
+    // ClientHello.random.
+    pub const SEED: [u8; 64] = [0u8; 64];
+
+    pub const MAX_HMAC_OUTPUT_LEN: usize = 512 / 8;
+
+    // P_hash(secret, label + seed) from RFC 5246 Section 5, which is what the
+    // TLS 1.2 PRF is for all the cipher suites we care about. `hmac(parts,
+    // out)` must write HMAC(secret, parts[0] || parts[1] || ...) into `out`,
+    // which is `hmac_output_len` bytes long.
+    pub fn p_hash<F>(out: &mut [u8], hmac_output_len: usize, label: &[u8],
+                     seed: &[u8], mut hmac: F)
+                     where F: FnMut(&[&[u8]], &mut [u8]) {
+        let mut a = [0u8; MAX_HMAC_OUTPUT_LEN];
+        let mut block = [0u8; MAX_HMAC_OUTPUT_LEN];
+
+        // A(1) = HMAC(secret, label + seed).
+        hmac(&[label, seed], &mut a[..hmac_output_len]);
+
+        for (i, chunk) in out.chunks_mut(hmac_output_len).enumerate() {
+            if i > 0 {
+                // A(i + 1) = HMAC(secret, A(i)).
+                let prev_a = a;
+                hmac(&[&prev_a[..hmac_output_len]],
+                     &mut a[..hmac_output_len]);
+            }
+            hmac(&[&a[..hmac_output_len], label, seed],
+                 &mut block[..hmac_output_len]);
+            let chunk_len = chunk.len();
+            chunk.copy_from_slice(&block[..chunk_len]);
+        }
+    }
+
+    // Known answers for `p_hash`, from the TLS 1.2 PRF test vectors that were
+    // posted to the IETF TLS working group's mailing list.
+    pub struct TestVector {
+        pub secret: &'static [u8],
+        pub label: &'static [u8],
+        pub seed: &'static [u8],
+        pub output: &'static [u8],
+    }
+
+    pub const P_SHA256_TEST_VECTOR: TestVector = TestVector {
+        secret: &[
+            0x9b, 0xbe, 0x43, 0x6b, 0xa9, 0x40, 0xf0, 0x17, 0xb1, 0x76, 0x52,
+            0x84, 0x9a, 0x71, 0xdb, 0x35,
+        ],
+        label: b"test label",
+        seed: &[
+            0xa0, 0xba, 0x9f, 0x93, 0x6c, 0xda, 0x31, 0x18, 0x27, 0xa6, 0xf7,
+            0x96, 0xff, 0xd5, 0x19, 0x8c,
+        ],
+        output: &[
+            0xe3, 0xf2, 0x29, 0xba, 0x72, 0x7b, 0xe1, 0x7b, 0x8d, 0x12, 0x26,
+            0x20, 0x55, 0x7c, 0xd4, 0x53, 0xc2, 0xaa, 0xb2, 0x1d, 0x07, 0xc3,
+            0xd4, 0x95, 0x32, 0x9b, 0x52, 0xd4, 0xe6, 0x1e, 0xdb, 0x5a, 0x6b,
+            0x30, 0x17, 0x91, 0xe9, 0x0d, 0x35, 0xc9, 0xc9, 0xa4, 0x6b, 0x4e,
+            0x14, 0xba, 0xf9, 0xaf, 0x0f, 0xa0, 0x22, 0xf7, 0x07, 0x7d, 0xef,
+            0x17, 0xab, 0xfd, 0x37, 0x97, 0xc0, 0x56, 0x4b, 0xab, 0x4f, 0xbc,
+            0x91, 0x66, 0x6e, 0x9d, 0xef, 0x9b, 0x97, 0xfc, 0xe3, 0x4f, 0x79,
+            0x67, 0x89, 0xba, 0xa4, 0x80, 0x82, 0xd1, 0x22, 0xee, 0x42, 0xc5,
+            0xa7, 0x2e, 0x5a, 0x51, 0x10, 0xff, 0xf7, 0x01, 0x87, 0x34, 0x7b,
+            0x66,
+        ],
+    };
+
+    pub const P_SHA384_TEST_VECTOR: TestVector = TestVector {
+        secret: &[
+            0xb8, 0x0b, 0x73, 0x3d, 0x6c, 0xee, 0xfc, 0xdc, 0x71, 0x56, 0x6e,
+            0xa4, 0x8e, 0x55, 0x67, 0xdf,
+        ],
+        label: b"test label",
+        seed: &[
+            0xcd, 0x66, 0x5c, 0xf6, 0xa8, 0x44, 0x7d, 0xd6, 0xff, 0x8b, 0x27,
+            0x55, 0x5e, 0xdb, 0x74, 0x65,
+        ],
+        output: &[
+            0x7b, 0x0c, 0x18, 0xe9, 0xce, 0xd4, 0x10, 0xed, 0x18, 0x04, 0xf2,
+            0xcf, 0xa3, 0x4a, 0x33, 0x6a, 0x1c, 0x14, 0xdf, 0xfb, 0x49, 0x00,
+            0xbb, 0x5f, 0xd7, 0x94, 0x21, 0x07, 0xe8, 0x1c, 0x83, 0xcd, 0xe9,
+            0xca, 0x0f, 0xaa, 0x60, 0xbe, 0x9f, 0xe3, 0x4f, 0x82, 0xb1, 0x23,
+            0x3c, 0x91, 0x46, 0xa0, 0xe5, 0x34, 0xcb, 0x40, 0x0f, 0xed, 0x27,
+            0x00, 0x88, 0x4f, 0x9d, 0xc2, 0x36, 0xf8, 0x0e, 0xdd, 0x8b, 0xfa,
+            0x96, 0x11, 0x44, 0xc9, 0xe8, 0xd7, 0x92, 0xec, 0xa7, 0x22, 0xa7,
+            0xb3, 0x2f, 0xc3, 0xd4, 0x16, 0xd4, 0x73, 0xeb, 0xc2, 0xc5, 0xfd,
+            0x4a, 0xbf, 0xda, 0xd0, 0x5d, 0x91, 0x84, 0x25, 0x9b, 0x5b, 0xf8,
+            0xcd, 0x4d, 0x90, 0xfa, 0x0d, 0x31, 0xe2, 0xde, 0xc4, 0x79, 0xe4,
+            0xf1, 0xa2, 0x60, 0x66, 0xf2, 0xee, 0xa9, 0xa6, 0x92, 0x36, 0xa3,
+            0xe5, 0x26, 0x55, 0xc9, 0xe9, 0xae, 0xe6, 0x91, 0xc8, 0xf3, 0xa2,
+            0x68, 0x54, 0x30, 0x8d, 0x5e, 0xaa, 0x3b, 0xe8, 0x5e, 0x09, 0x90,
+            0x70, 0x3d, 0x73, 0xe5, 0x6f,
+        ],
+    };
+}
+
+#[macro_export]
+macro_rules! tls12_prf_bench {
+    ( $bench_fn_name:ident, $secret_len:expr, $label_value:expr,
+      $out_len:expr, $secret:ident, $label:ident, $seed:ident, $out:ident,
+      $calculation:expr) => {
+        #[bench]
+        fn $bench_fn_name(b: &mut test::Bencher) {
+            let secret = [0u8; $secret_len];
+            let $secret = &secret[..];
+            let $label = $label_value;
+            let $seed = &$crate::tls12_prf::SEED[..];
+            let mut $out = [0u8; $out_len];
+            b.iter(|| $calculation)
+        }
+    }
+}
+
+// Checks that `$calculation` computes `$test_vector`'s output.
+#[macro_export]
+macro_rules! tls12_prf_test {
+    ( $test_vector:expr, $secret:ident, $label:ident, $seed:ident,
+      $out:ident, $calculation:expr) => {
+        #[test]
+        fn test_vector() {
+            let test_vector = $test_vector;
+            let $secret = test_vector.secret;
+            let $label = test_vector.label;
+            let $seed = test_vector.seed;
+            let mut $out = vec![0u8; test_vector.output.len()];
+            $calculation;
+            assert_eq!(&$out[..], test_vector.output);
+        }
+    }
+}
+
+// `$calculation` must construct the HMAC key from `$secret` and then compute
+// P_hash using `crypto_bench::tls12_prf::p_hash`; in TLS every handshake uses
+// a different secret so the HMAC key setup is part of the cost.
+// `$test_vector` is the `tls12_prf::TestVector` for the hash function.
+#[macro_export]
+macro_rules! tls12_prf_benches {
+    ( $test_vector:expr, $secret:ident, $label:ident, $seed:ident,
+      $out:ident, $calculation:expr) => {
+        use test;
+
+        tls12_prf_test!($test_vector, $secret, $label, $seed, $out,
+                        $calculation);
+
+        tls12_prf_bench!(master_secret,
+                         $crate::tls12_prf::PREMASTER_SECRET_LEN,
+                         $crate::tls12_prf::MASTER_SECRET_LABEL,
+                         $crate::tls12_prf::MASTER_SECRET_LEN,
+                         $secret, $label, $seed, $out, $calculation);
+
+        // The key block for the AES-128-GCM cipher suites: two 16-byte keys
+        // and two 4-byte implicit nonces.
+        tls12_prf_bench!(key_expansion_40,
+                         $crate::tls12_prf::MASTER_SECRET_LEN,
+                         $crate::tls12_prf::KEY_EXPANSION_LABEL, 40,
+                         $secret, $label, $seed, $out, $calculation);
+
+        // The key block for the AES-256-GCM cipher suites: two 32-byte keys
+        // and two 4-byte implicit nonces. (The ChaCha20-Poly1305 key block is
+        // 88 bytes, which requires the same number of HMAC blocks.)
+        tls12_prf_bench!(key_expansion_72,
+                         $crate::tls12_prf::MASTER_SECRET_LEN,
+                         $crate::tls12_prf::KEY_EXPANSION_LABEL, 72,
+                         $secret, $label, $seed, $out, $calculation);
+
+        // The key block for the AES-256-CBC-SHA cipher suites: two 20-byte
+        // MAC keys and two 32-byte keys.
+        tls12_prf_bench!(key_expansion_104,
+                         $crate::tls12_prf::MASTER_SECRET_LEN,
+                         $crate::tls12_prf::KEY_EXPANSION_LABEL, 104,
+                         $secret, $label, $seed, $out, $calculation);
+    }
+}
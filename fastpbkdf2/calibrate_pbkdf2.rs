@@ -0,0 +1,27 @@
+// Finds, for each PBKDF2 PRF, the number of iterations that fastpbkdf2 can
+// compute in a given amount of time. See `../calibrate_pbkdf2`.
+
+extern crate crypto_bench;
+extern crate fastpbkdf2;
+
+use crypto_bench::pbkdf2::{PASSWORD, SALT};
+use crypto_bench::pbkdf2::calibrate;
+use std::time::Duration;
+
+fn report(prf_name: &str, out_len: usize, derive: fn(&[u8], &[u8], u32,
+                                                     &mut [u8]),
+          target: Duration) {
+    calibrate::report("fastpbkdf2", prf_name, target, out_len,
+                      |iterations, out| derive(PASSWORD, SALT, iterations, out));
+}
+
+fn main() {
+    let target = calibrate::target_from_args();
+    report("hmac_sha1", crypto_bench::SHA1_OUTPUT_LEN,
+           fastpbkdf2::pbkdf2_hmac_sha1, target);
+    report("hmac_sha256", crypto_bench::SHA256_OUTPUT_LEN,
+           fastpbkdf2::pbkdf2_hmac_sha256, target);
+    calibrate::report_unsupported("fastpbkdf2", "hmac_sha384");
+    report("hmac_sha512", crypto_bench::SHA512_OUTPUT_LEN,
+           fastpbkdf2::pbkdf2_hmac_sha512, target);
+}
@@ -8,25 +8,28 @@ extern crate crypto_bench;
 extern crate fastpbkdf2;
 
 mod pbkdf2 {
-    use crypto_bench;
-    use fastpbkdf2;
-    use test;
-
-    pbkdf2_bench!(hmac_sha1, crypto_bench::SHA1_OUTPUT_LEN, out,
-                  fastpbkdf2::pbkdf2_hmac_sha1(crypto_bench::pbkdf2::PASSWORD,
-                                               crypto_bench::pbkdf2::SALT,
-                                               crypto_bench::pbkdf2::ITERATIONS,
-                                               &mut out));
-
-    pbkdf2_bench!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN, out,
-                  fastpbkdf2::pbkdf2_hmac_sha256(crypto_bench::pbkdf2::PASSWORD,
-                                                 crypto_bench::pbkdf2::SALT,
-                                                 crypto_bench::pbkdf2::ITERATIONS,
-                                                 &mut out));
-
-    pbkdf2_bench!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN, out,
-                  fastpbkdf2::pbkdf2_hmac_sha512(crypto_bench::pbkdf2::PASSWORD,
-                                                 crypto_bench::pbkdf2::SALT,
-                                                 crypto_bench::pbkdf2::ITERATIONS,
-                                                 &mut out));
+    macro_rules! fastpbkdf2_pbkdf2_benches {
+        ( $name:ident, $output_len:expr, $derive:path) => {
+            mod $name {
+                use crypto_bench;
+                use fastpbkdf2;
+
+                pbkdf2_benches!($output_len, out,
+                                $derive(crypto_bench::pbkdf2::PASSWORD,
+                                        crypto_bench::pbkdf2::SALT,
+                                        crypto_bench::pbkdf2::ITERATIONS,
+                                        &mut out));
+            }
+        }
+    }
+
+    fastpbkdf2_pbkdf2_benches!(hmac_sha1, crypto_bench::SHA1_OUTPUT_LEN,
+                               fastpbkdf2::pbkdf2_hmac_sha1);
+    fastpbkdf2_pbkdf2_benches!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN,
+                               fastpbkdf2::pbkdf2_hmac_sha256);
+
+    // fastpbkdf2 doesn't implement PBKDF2-HMAC-SHA384.
+
+    fastpbkdf2_pbkdf2_benches!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN,
+                               fastpbkdf2::pbkdf2_hmac_sha512);
 }
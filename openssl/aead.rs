@@ -0,0 +1,143 @@
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use openssl::error::ErrorStack;
+use openssl::rand;
+use test;
+
+// All the AEADs we're testing use 128-bit tags.
+pub const TAG_LEN: usize = 128 / 8;
+
+pub fn generate_key(cipher: &CipherRef) -> Vec<u8> {
+    let mut key_bytes = vec![0u8; cipher.key_length()];
+    rand::rand_bytes(&mut key_bytes).unwrap();
+    key_bytes
+}
+
+// `in_out` is the plaintext followed by room for the tag. `ctx` must have
+// been initialized for encryption with the cipher and key already; only the
+// nonce is set here, so the key schedule is reused.
+pub fn seal_in_place(ctx: &mut CipherCtx, nonce: &[u8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - TAG_LEN;
+    try!(ctx.encrypt_init(None, None, Some(nonce)));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update_inplace(&mut in_out[..chunk_len], chunk_len));
+    try!(ctx.cipher_final(&mut []));
+    ctx.tag(&mut in_out[chunk_len..])
+}
+
+// `in_out` is the ciphertext followed by the tag. `ctx` must have been
+// initialized for decryption with the cipher and key already.
+pub fn open_in_place(ctx: &mut CipherCtx, nonce: &[u8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - TAG_LEN;
+    let (in_out, tag) = in_out.split_at_mut(chunk_len);
+    try!(ctx.decrypt_init(None, None, Some(nonce)));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update_inplace(in_out, chunk_len));
+    try!(ctx.set_tag(tag));
+    try!(ctx.cipher_final(&mut []));
+    Ok(())
+}
+
+fn seal_in_place_bench(cipher: &'static CipherRef, chunk_len: usize,
+                       ad: &[u8], counter: crypto_bench::aead::Counter,
+                       b: &mut test::Bencher) {
+    let key = generate_key(cipher);
+    let mut ctx = CipherCtx::new().unwrap();
+    ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+
+    let mut in_out = vec![0u8; chunk_len + TAG_LEN];
+
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                  TAG_LEN).bytes(counter);
+
+    b.iter(|| {
+        seal_in_place(&mut ctx, &crypto_bench::aead::NONCE, ad, &mut in_out)
+            .unwrap();
+    });
+}
+
+fn seal_records_bench(cipher: &'static CipherRef,
+                      version: crypto_bench::aead::TlsVersion,
+                      chunk_len: usize, counter: crypto_bench::aead::Counter,
+                      b: &mut test::Bencher) {
+    let key = generate_key(cipher);
+    let mut ctx = CipherCtx::new().unwrap();
+    ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+
+    let mut in_out = vec![0u8; chunk_len + TAG_LEN];
+
+    let mut records = crypto_bench::aead::Records::new(version, chunk_len);
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len,
+                                                  records.ad().len(),
+                                                  TAG_LEN).bytes(counter);
+
+    b.iter(|| {
+        records.advance();
+        seal_in_place(&mut ctx, records.nonce(), records.ad(), &mut in_out)
+            .unwrap();
+    });
+}
+
+// Key setup is done once per connection, not once per record, so the
+// `seal_in_place` and `open_in_place` benchmarks exclude it; they only set the
+// nonce. These benchmarks measure initializing a context with a new key. The
+// `open_in_place` benchmarks are Criterion benchmarks, in `open_in_place.rs`.
+macro_rules! openssl_key_setup_benches {
+    ( $name:ident, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use openssl::cipher_ctx::CipherCtx;
+            use test;
+
+            #[bench]
+            fn sealing(b: &mut test::Bencher) {
+                let key = super::super::generate_key($cipher);
+                let mut ctx = CipherCtx::new().unwrap();
+                b.iter(|| {
+                    ctx.encrypt_init(Some($cipher), Some(&key), None).unwrap();
+                });
+            }
+
+            #[bench]
+            fn opening(b: &mut test::Bencher) {
+                let key = super::super::generate_key($cipher);
+                let mut ctx = CipherCtx::new().unwrap();
+                b.iter(|| {
+                    ctx.decrypt_init(Some($cipher), Some(&key), None).unwrap();
+                });
+            }
+        }
+    }
+}
+
+mod key_setup {
+    openssl_key_setup_benches!(aes_128_gcm, Cipher::aes_128_gcm());
+    openssl_key_setup_benches!(aes_256_gcm, Cipher::aes_256_gcm());
+    openssl_key_setup_benches!(chacha20_poly1305, Cipher::chacha20_poly1305());
+}
+
+mod seal_in_place {
+    use super::seal_in_place_bench;
+
+    aead_benches!(aes_128_gcm, aead_seal_in_place_bench, seal_in_place_bench,
+                  ::openssl::cipher::Cipher::aes_128_gcm());
+    aead_benches!(aes_256_gcm, aead_seal_in_place_bench, seal_in_place_bench,
+                  ::openssl::cipher::Cipher::aes_256_gcm());
+    aead_benches!(chacha20_poly1305, aead_seal_in_place_bench,
+                  seal_in_place_bench,
+                  ::openssl::cipher::Cipher::chacha20_poly1305());
+}
+
+mod seal_records {
+    use super::seal_records_bench;
+
+    aead_seal_records_benches!(aes_128_gcm, seal_records_bench,
+                               ::openssl::cipher::Cipher::aes_128_gcm());
+    aead_seal_records_benches!(aes_256_gcm, seal_records_bench,
+                               ::openssl::cipher::Cipher::aes_256_gcm());
+    aead_seal_records_benches!(chacha20_poly1305, seal_records_bench,
+                               ::openssl::cipher::Cipher::chacha20_poly1305());
+}
@@ -0,0 +1,103 @@
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+
+use aead::generate_key;
+
+// AES-ECB without padding, which is raw AES applied to each block.
+pub struct BlockCipher {
+    encrypting_ctx: CipherCtx,
+    decrypting_ctx: CipherCtx,
+}
+
+impl BlockCipher {
+    pub fn new(cipher: &'static CipherRef) -> BlockCipher {
+        let key = generate_key(cipher);
+        let mut encrypting_ctx = CipherCtx::new().unwrap();
+        encrypting_ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+        encrypting_ctx.set_padding(false);
+        let mut decrypting_ctx = CipherCtx::new().unwrap();
+        decrypting_ctx.decrypt_init(Some(cipher), Some(&key), None).unwrap();
+        decrypting_ctx.set_padding(false);
+        BlockCipher {
+            encrypting_ctx: encrypting_ctx,
+            decrypting_ctx: decrypting_ctx,
+        }
+    }
+}
+
+impl crypto_bench::block_cipher::BlockCipher for BlockCipher {
+    fn encrypt(&mut self, in_out: &mut [u8], len: usize) {
+        self.encrypting_ctx.cipher_update_inplace(in_out, len).unwrap();
+    }
+
+    fn decrypt(&mut self, in_out: &mut [u8], len: usize) {
+        self.decrypting_ctx.cipher_update_inplace(in_out, len).unwrap();
+    }
+}
+
+macro_rules! openssl_aes_benches {
+    ( $name:ident, $bench:ident, $cipher:expr ) => {
+        mod $name {
+            use aes::BlockCipher;
+            use openssl::cipher::Cipher;
+
+            block_cipher_benches!($bench, BlockCipher::new($cipher));
+        }
+    }
+}
+
+mod encrypt {
+    openssl_aes_benches!(aes_128, block_cipher_encrypt_bench,
+                         Cipher::aes_128_ecb());
+    openssl_aes_benches!(aes_192, block_cipher_encrypt_bench,
+                         Cipher::aes_192_ecb());
+    openssl_aes_benches!(aes_256, block_cipher_encrypt_bench,
+                         Cipher::aes_256_ecb());
+}
+
+mod decrypt {
+    openssl_aes_benches!(aes_128, block_cipher_decrypt_bench,
+                         Cipher::aes_128_ecb());
+    openssl_aes_benches!(aes_192, block_cipher_decrypt_bench,
+                         Cipher::aes_192_ecb());
+    openssl_aes_benches!(aes_256, block_cipher_decrypt_bench,
+                         Cipher::aes_256_ecb());
+}
+
+// The key schedule is computed when the key is set on an existing context.
+mod key_setup {
+    use aead::generate_key;
+    use openssl::cipher::Cipher;
+    use openssl::cipher_ctx::CipherCtx;
+    use test;
+
+    macro_rules! openssl_aes_key_setup_benches {
+        ( $encrypt_name:ident, $decrypt_name:ident, $cipher:expr ) => {
+            #[bench]
+            fn $encrypt_name(b: &mut test::Bencher) {
+                let key = generate_key($cipher);
+                let mut ctx = CipherCtx::new().unwrap();
+                b.iter(|| {
+                    ctx.encrypt_init(Some($cipher), Some(&key), None).unwrap();
+                });
+            }
+
+            #[bench]
+            fn $decrypt_name(b: &mut test::Bencher) {
+                let key = generate_key($cipher);
+                let mut ctx = CipherCtx::new().unwrap();
+                b.iter(|| {
+                    ctx.decrypt_init(Some($cipher), Some(&key), None).unwrap();
+                });
+            }
+        }
+    }
+
+    openssl_aes_key_setup_benches!(aes_128_encrypt, aes_128_decrypt,
+                                   Cipher::aes_128_ecb());
+    openssl_aes_key_setup_benches!(aes_192_encrypt, aes_192_decrypt,
+                                   Cipher::aes_192_ecb());
+    openssl_aes_key_setup_benches!(aes_256_encrypt, aes_256_decrypt,
+                                   Cipher::aes_256_ecb());
+}
@@ -0,0 +1,122 @@
+// OpenSSL generates the private key and computes the public key in a single
+// step, so there are no `generate_private_key` benchmarks. Its private keys
+// aren't consumed by the key agreement, unlike *ring*'s.
+
+use openssl::bn::BigNumContext;
+use openssl::derive::Deriver;
+use openssl::ec::{EcGroup, EcKey, EcPoint, PointConversionForm};
+use openssl::nid::Nid;
+use openssl::pkey::{Id, PKey, PKeyRef, Private, Public};
+
+// The length of a P-384 shared secret.
+const SHARED_SECRET_MAX_LEN: usize = 384 / 8;
+
+enum Curve {
+    Ec(EcGroup),
+    X25519,
+}
+
+impl Curve {
+    fn p256() -> Curve {
+        Curve::Ec(EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap())
+    }
+
+    fn p384() -> Curve {
+        Curve::Ec(EcGroup::from_curve_name(Nid::SECP384R1).unwrap())
+    }
+
+    fn generate_key_pair(&self) -> PKey<Private> {
+        match *self {
+            Curve::Ec(ref group) => {
+                PKey::from_ec_key(EcKey::generate(group).unwrap()).unwrap()
+            },
+            Curve::X25519 => PKey::generate_x25519().unwrap(),
+        }
+    }
+
+    // Encodes the public key the way it's sent to the peer, as *ring* does.
+    fn public_key(&self, key_pair: &PKeyRef<Private>) -> Vec<u8> {
+        match *self {
+            Curve::Ec(ref group) => {
+                let mut ctx = BigNumContext::new().unwrap();
+                key_pair.ec_key().unwrap().public_key()
+                    .to_bytes(group, PointConversionForm::UNCOMPRESSED,
+                              &mut ctx)
+                    .unwrap()
+            },
+            Curve::X25519 => key_pair.raw_public_key().unwrap(),
+        }
+    }
+
+    fn parse_public_key(&self, public_key: &[u8]) -> PKey<Public> {
+        match *self {
+            Curve::Ec(ref group) => {
+                let mut ctx = BigNumContext::new().unwrap();
+                let point =
+                    EcPoint::from_bytes(group, public_key, &mut ctx).unwrap();
+                let ec_key = EcKey::from_public_key(group, &point).unwrap();
+                PKey::from_ec_key(ec_key).unwrap()
+            },
+            Curve::X25519 => {
+                PKey::public_key_from_raw_bytes(public_key, Id::X25519)
+                    .unwrap()
+            },
+        }
+    }
+
+    // Parses the peer's public key and computes the shared secret, like
+    // `ring::agreement::agree_ephemeral`.
+    fn agree(&self, key_pair: &PKeyRef<Private>, peer_public_key: &[u8]) {
+        let peer_public_key = self.parse_public_key(peer_public_key);
+        let mut deriver = Deriver::new(key_pair).unwrap();
+        deriver.set_peer(&peer_public_key).unwrap();
+        let mut shared_secret = [0u8; SHARED_SECRET_MAX_LEN];
+        deriver.derive(&mut shared_secret).unwrap();
+    }
+}
+
+macro_rules! openssl_agreement_benches {
+    ( $name:ident, $curve:expr ) => {
+        mod $name {
+            use agreement::Curve;
+            use test;
+
+            #[bench]
+            fn generate_key_pair(b: &mut test::Bencher) {
+                let curve = $curve;
+                b.iter(|| {
+                    let key_pair = curve.generate_key_pair();
+                    curve.public_key(&key_pair)
+                });
+            }
+
+            #[bench]
+            fn agree_ephemeral(b: &mut test::Bencher) {
+                let curve = $curve;
+
+                // These operations are done by the peer.
+                let b_public = curve.public_key(&curve.generate_key_pair());
+
+                let a_key_pair = curve.generate_key_pair();
+                b.iter(|| curve.agree(&a_key_pair, &b_public));
+            }
+
+            #[bench]
+            fn generate_key_pair_and_agree_ephemeral(b: &mut test::Bencher) {
+                let curve = $curve;
+
+                // These operations are done by the peer.
+                let b_public = curve.public_key(&curve.generate_key_pair());
+
+                b.iter(|| {
+                    let a_key_pair = curve.generate_key_pair();
+                    curve.agree(&a_key_pair, &b_public);
+                });
+            }
+        }
+    }
+}
+
+openssl_agreement_benches!(p256, Curve::p256());
+openssl_agreement_benches!(p384, Curve::p384());
+openssl_agreement_benches!(x25519, Curve::X25519);
@@ -0,0 +1,31 @@
+// Finds, for each PBKDF2 PRF, the number of iterations that OpenSSL can
+// compute in a given amount of time. See `../calibrate_pbkdf2`.
+
+extern crate crypto_bench;
+extern crate openssl;
+
+use crypto_bench::pbkdf2::{PASSWORD, SALT};
+use crypto_bench::pbkdf2::calibrate;
+use openssl::hash::MessageDigest;
+use openssl::pkcs5;
+use std::time::Duration;
+
+fn report(prf_name: &str, out_len: usize, digest: MessageDigest,
+          target: Duration) {
+    calibrate::report("openssl", prf_name, target, out_len, |iterations, out| {
+        pkcs5::pbkdf2_hmac(PASSWORD, SALT, iterations as usize, digest, out)
+            .unwrap()
+    });
+}
+
+fn main() {
+    let target = calibrate::target_from_args();
+    report("hmac_sha1", crypto_bench::SHA1_OUTPUT_LEN, MessageDigest::sha1(),
+           target);
+    report("hmac_sha256", crypto_bench::SHA256_OUTPUT_LEN,
+           MessageDigest::sha256(), target);
+    report("hmac_sha384", crypto_bench::SHA384_OUTPUT_LEN,
+           MessageDigest::sha384(), target);
+    report("hmac_sha512", crypto_bench::SHA512_OUTPUT_LEN,
+           MessageDigest::sha512(), target);
+}
@@ -0,0 +1,99 @@
+use crypto_bench;
+use crypto_bench::cbc_hmac::BLOCK_LEN;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::rand;
+use openssl::sign::Signer;
+
+struct CbcHmac {
+    encrypting_ctx: CipherCtx,
+    decrypting_ctx: CipherCtx,
+    digest: MessageDigest,
+    mac_key: PKey<Private>,
+}
+
+impl CbcHmac {
+    fn new(cipher: &'static CipherRef, digest: MessageDigest) -> CbcHmac {
+        let mut key = vec![0u8; cipher.key_length()];
+        rand::rand_bytes(&mut key).unwrap();
+
+        // The MAC key is as long as the digest output in TLS.
+        let mut mac_key = vec![0u8; digest.size()];
+        rand::rand_bytes(&mut mac_key).unwrap();
+
+        let mut encrypting_ctx = CipherCtx::new().unwrap();
+        encrypting_ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+        encrypting_ctx.set_padding(false);
+        let mut decrypting_ctx = CipherCtx::new().unwrap();
+        decrypting_ctx.decrypt_init(Some(cipher), Some(&key), None).unwrap();
+        decrypting_ctx.set_padding(false);
+
+        CbcHmac {
+            encrypting_ctx: encrypting_ctx,
+            decrypting_ctx: decrypting_ctx,
+            digest: digest,
+            mac_key: PKey::hmac(&mac_key).unwrap(),
+        }
+    }
+}
+
+impl crypto_bench::cbc_hmac::CbcHmac for CbcHmac {
+    fn mac_len(&self) -> usize { self.digest.size() }
+
+    fn sign(&mut self, parts: &[&[u8]], out: &mut [u8]) {
+        let mut signer = Signer::new(self.digest, &self.mac_key).unwrap();
+        for part in parts {
+            signer.update(part).unwrap();
+        }
+        signer.sign(out).unwrap();
+    }
+
+    // Only the IV is set, so the key schedule is reused.
+    fn encrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+               len: usize) {
+        self.encrypting_ctx.encrypt_init(None, None, Some(iv)).unwrap();
+        self.encrypting_ctx.cipher_update_inplace(in_out, len).unwrap();
+    }
+
+    fn decrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+               len: usize) {
+        self.decrypting_ctx.decrypt_init(None, None, Some(iv)).unwrap();
+        self.decrypting_ctx.cipher_update_inplace(in_out, len).unwrap();
+    }
+}
+
+macro_rules! openssl_cbc_hmac_benches {
+    ( $name:ident, $bench:ident, $cipher:expr, $digest:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use openssl::hash::MessageDigest;
+            use super::super::CbcHmac;
+
+            cbc_hmac_benches!($bench, CbcHmac::new($cipher, $digest));
+        }
+    }
+}
+
+mod seal {
+    openssl_cbc_hmac_benches!(aes_128_cbc_sha, cbc_hmac_seal_bench,
+                              Cipher::aes_128_cbc(), MessageDigest::sha1());
+    openssl_cbc_hmac_benches!(aes_128_cbc_sha256, cbc_hmac_seal_bench,
+                              Cipher::aes_128_cbc(), MessageDigest::sha256());
+    openssl_cbc_hmac_benches!(aes_256_cbc_sha, cbc_hmac_seal_bench,
+                              Cipher::aes_256_cbc(), MessageDigest::sha1());
+    openssl_cbc_hmac_benches!(aes_256_cbc_sha256, cbc_hmac_seal_bench,
+                              Cipher::aes_256_cbc(), MessageDigest::sha256());
+}
+
+mod open {
+    openssl_cbc_hmac_benches!(aes_128_cbc_sha, cbc_hmac_open_bench,
+                              Cipher::aes_128_cbc(), MessageDigest::sha1());
+    openssl_cbc_hmac_benches!(aes_128_cbc_sha256, cbc_hmac_open_bench,
+                              Cipher::aes_128_cbc(), MessageDigest::sha256());
+    openssl_cbc_hmac_benches!(aes_256_cbc_sha, cbc_hmac_open_bench,
+                              Cipher::aes_256_cbc(), MessageDigest::sha1());
+    openssl_cbc_hmac_benches!(aes_256_cbc_sha256, cbc_hmac_open_bench,
+                              Cipher::aes_256_cbc(), MessageDigest::sha256());
+}
@@ -0,0 +1,90 @@
+// AES-CCM, with the nonce and tag lengths of `crypto_bench::aead::AES_CCM` and
+// `crypto_bench::aead::AES_CCM_8`.
+
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use openssl::error::ErrorStack;
+use test;
+
+use aead::generate_key;
+
+// The nonce and tag lengths must be set before the key, and then they are
+// kept when each message reinitializes the context with just the nonce.
+pub fn new_keys(cipher: &CipherRef, lengths: crypto_bench::aead::Lengths)
+                -> (CipherCtx, CipherCtx) {
+    let key = generate_key(cipher);
+
+    let mut sealing_ctx = CipherCtx::new().unwrap();
+    sealing_ctx.encrypt_init(Some(cipher), None, None).unwrap();
+    sealing_ctx.set_iv_length(lengths.nonce).unwrap();
+    sealing_ctx.set_tag_length(lengths.tag).unwrap();
+    sealing_ctx.encrypt_init(None, Some(&key), None).unwrap();
+
+    let mut opening_ctx = CipherCtx::new().unwrap();
+    opening_ctx.decrypt_init(Some(cipher), None, None).unwrap();
+    opening_ctx.set_iv_length(lengths.nonce).unwrap();
+    opening_ctx.set_tag_length(lengths.tag).unwrap();
+    opening_ctx.decrypt_init(None, Some(&key), None).unwrap();
+
+    (sealing_ctx, opening_ctx)
+}
+
+// `in_out` is the plaintext followed by room for the tag. CCM has to know the
+// length of the plaintext before it authenticates the additional data.
+pub fn seal_in_place(ctx: &mut CipherCtx, nonce: &[u8], ad: &[u8],
+                     in_out: &mut [u8], tag_len: usize)
+                     -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - tag_len;
+    try!(ctx.encrypt_init(None, None, Some(nonce)));
+    try!(ctx.set_data_len(chunk_len));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update_inplace(&mut in_out[..chunk_len], chunk_len));
+    try!(ctx.cipher_final(&mut []));
+    ctx.tag(&mut in_out[chunk_len..])
+}
+
+// `in_out` is the ciphertext followed by the tag. OpenSSL verifies the tag
+// when the ciphertext is decrypted, so there's no `cipher_final`.
+pub fn open_in_place(ctx: &mut CipherCtx, nonce: &[u8], ad: &[u8],
+                     in_out: &mut [u8], tag_len: usize)
+                     -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - tag_len;
+    let (in_out, tag) = in_out.split_at_mut(chunk_len);
+    try!(ctx.decrypt_init(None, None, Some(nonce)));
+    try!(ctx.set_tag(tag));
+    try!(ctx.set_data_len(chunk_len));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update_inplace(in_out, chunk_len));
+    Ok(())
+}
+
+fn seal_in_place_bench(cipher: &CipherRef,
+                       lengths: crypto_bench::aead::Lengths,
+                       chunk_len: usize, ad: &[u8],
+                       counter: crypto_bench::aead::Counter,
+                       b: &mut test::Bencher) {
+    let (mut ctx, _) = new_keys(cipher, lengths);
+
+    let mut in_out = vec![0u8; chunk_len + lengths.tag];
+
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                  lengths.tag).bytes(counter);
+
+    b.iter(|| {
+        seal_in_place(&mut ctx, lengths.nonce(), ad, &mut in_out,
+                      lengths.tag).unwrap();
+    });
+}
+
+mod seal_in_place {
+    use super::seal_in_place_bench;
+
+    aead_benches!(aes_128_ccm, aead_seal_in_place_bench, seal_in_place_bench,
+                  ::openssl::cipher::Cipher::aes_128_ccm(),
+                  ::crypto_bench::aead::AES_CCM);
+    aead_benches!(aes_128_ccm_8, aead_seal_in_place_bench,
+                  seal_in_place_bench,
+                  ::openssl::cipher::Cipher::aes_128_ccm(),
+                  ::crypto_bench::aead::AES_CCM_8);
+}
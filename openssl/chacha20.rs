@@ -0,0 +1,37 @@
+use openssl::cipher::Cipher;
+use openssl::cipher_ctx::CipherCtx;
+use test;
+
+use aead::generate_key;
+
+// OpenSSL's ChaCha20 IV is a 32-bit block counter followed by the nonce.
+const IV_LEN: usize = 128 / 8;
+
+// Every message has a new nonce, as in ChaCha20-Poly1305. Only the IV is set
+// for each message, so the key is reused.
+fn xor_bench(len: usize, b: &mut test::Bencher) {
+    let cipher = Cipher::chacha20();
+    let key = generate_key(cipher);
+    let mut ctx = CipherCtx::new().unwrap();
+    ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+    let iv = [0u8; IV_LEN];
+    let mut in_out = vec![0u8; len];
+    b.bytes = len as u64;
+    b.iter(|| {
+        ctx.encrypt_init(None, None, Some(&iv)).unwrap();
+        ctx.cipher_update_inplace(&mut in_out, len).unwrap();
+    });
+}
+
+macro_rules! openssl_chacha20_bench {
+    ( $benchmark_name:ident, $len:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::xor_bench($len, b);
+        }
+    }
+}
+
+mod xor {
+    aead_part_benches!(openssl_chacha20_bench);
+}
@@ -0,0 +1,44 @@
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use test;
+
+use aead::generate_key;
+
+// The IV is the initial counter block.
+const IV_LEN: usize = 128 / 8;
+
+// Like `chacha20::xor_bench`, only the IV is set for each buffer, so the key
+// schedule is reused.
+fn xor_bench(len: usize, cipher: &CipherRef, b: &mut test::Bencher) {
+    let key = generate_key(cipher);
+    let mut ctx = CipherCtx::new().unwrap();
+    ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+    let iv = [0u8; IV_LEN];
+    let mut in_out = vec![0u8; len];
+    b.bytes = len as u64;
+    b.iter(|| {
+        ctx.encrypt_init(None, None, Some(&iv)).unwrap();
+        ctx.cipher_update_inplace(&mut in_out, len).unwrap();
+    });
+}
+
+macro_rules! openssl_ctr_bench {
+    ( $benchmark_name:ident, $len:expr, $cipher:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::xor_bench($len, $cipher, b);
+        }
+    }
+}
+
+mod aes_128 {
+    use openssl::cipher::Cipher;
+
+    bulk_benches!(openssl_ctr_bench, Cipher::aes_128_ctr());
+}
+
+mod aes_256 {
+    use openssl::cipher::Cipher;
+
+    bulk_benches!(openssl_ctr_bench, Cipher::aes_256_ctr());
+}
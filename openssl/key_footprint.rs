@@ -0,0 +1,22 @@
+// Reports how much memory each of OpenSSL's `many_keys::SealingKey`s takes,
+// for comparison with the `many_keys` benchmarks. Its `CipherCtx` is a
+// pointer to a context that C code allocates, so it is measured as the growth
+// of the resident set. See `../README.md`.
+
+extern crate crypto_bench;
+extern crate crypto_bench_openssl;
+extern crate openssl;
+
+use crypto_bench::footprint;
+use crypto_bench_openssl::many_keys::SealingKey;
+use openssl::cipher::{Cipher, CipherRef};
+
+fn report(name: &str, cipher: &'static CipherRef) {
+    footprint::report_resident("openssl", name, || SealingKey::new(cipher));
+}
+
+fn main() {
+    report("aes_128_gcm", Cipher::aes_128_gcm());
+    report("aes_256_gcm", Cipher::aes_256_gcm());
+    report("chacha20_poly1305", Cipher::chacha20_poly1305());
+}
@@ -0,0 +1,141 @@
+// OpenSSL's key wrap ciphers wrap or unwrap a whole key in each
+// `cipher_update`, so the context is reinitialized, without the key, for
+// every key.
+
+use crypto_bench::key_wrap::ICV_LEN;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use openssl::error::ErrorStack;
+use openssl::rand;
+use test;
+
+use aead::generate_key;
+
+fn new_ctx(cipher: &CipherRef, key: &[u8], encrypt: bool) -> CipherCtx {
+    let mut ctx = CipherCtx::new().unwrap();
+    if encrypt {
+        ctx.encrypt_init(Some(cipher), Some(key), None).unwrap();
+    } else {
+        ctx.decrypt_init(Some(cipher), Some(key), None).unwrap();
+    }
+    ctx
+}
+
+fn wrap(ctx: &mut CipherCtx, key: &[u8], out: &mut [u8])
+        -> Result<usize, ErrorStack> {
+    try!(ctx.encrypt_init(None, None, None));
+    ctx.cipher_update(key, Some(out))
+}
+
+fn unwrap(ctx: &mut CipherCtx, wrapped: &[u8], out: &mut [u8])
+          -> Result<usize, ErrorStack> {
+    try!(ctx.decrypt_init(None, None, None));
+    ctx.cipher_update(wrapped, Some(out))
+}
+
+fn generate_data_key(len: usize) -> Vec<u8> {
+    let mut key = vec![0u8; len];
+    rand::rand_bytes(&mut key).unwrap();
+    key
+}
+
+// OpenSSL wants room for a whole extra block in the output.
+fn out_len(key_len: usize) -> usize {
+    key_len + 2 * ICV_LEN
+}
+
+fn wrap_bench(key_len: usize, cipher: &CipherRef, b: &mut test::Bencher) {
+    let kek = generate_key(cipher);
+    let mut ctx = new_ctx(cipher, &kek, true);
+    let key = generate_data_key(key_len);
+    let mut out = vec![0u8; out_len(key_len)];
+    b.bytes = key_len as u64;
+    b.iter(|| {
+        wrap(&mut ctx, &key, &mut out).unwrap();
+    });
+}
+
+fn unwrap_bench(key_len: usize, cipher: &CipherRef, tampered: bool,
+                b: &mut test::Bencher) {
+    let kek = generate_key(cipher);
+    let key = generate_data_key(key_len);
+    let mut wrapped = vec![0u8; out_len(key_len)];
+    let wrapped_len =
+        wrap(&mut new_ctx(cipher, &kek, true), &key, &mut wrapped).unwrap();
+    wrapped.truncate(wrapped_len);
+    if tampered {
+        wrapped[wrapped_len - 1] ^= 1;
+    }
+
+    let mut ctx = new_ctx(cipher, &kek, false);
+    let mut out = vec![0u8; out_len(key_len)];
+    b.bytes = key_len as u64;
+    b.iter(|| {
+        let result = unwrap(&mut ctx, &wrapped, &mut out);
+        assert_eq!(result.is_ok(), !tampered);
+    });
+}
+
+macro_rules! openssl_wrap_bench {
+    ( $benchmark_name:ident, $key_len:expr, $cipher:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::super::wrap_bench($key_len, $cipher, b);
+        }
+    }
+}
+
+// Each key length is a module so that the timing of the integrity failure
+// path (`bad_wrapped_key`) is reported next to the success path (`valid`).
+macro_rules! openssl_unwrap_bench {
+    ( $name:ident, $key_len:expr, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use test;
+
+            #[bench]
+            fn valid(b: &mut test::Bencher) {
+                super::super::super::unwrap_bench($key_len, $cipher, false,
+                                                  b);
+            }
+
+            #[bench]
+            fn bad_wrapped_key(b: &mut test::Bencher) {
+                super::super::super::unwrap_bench($key_len, $cipher, true,
+                                                  b);
+            }
+        }
+    }
+}
+
+macro_rules! openssl_key_wrap_benches {
+    ( $name:ident, $bench:ident, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+
+            key_wrap_benches!($bench, $cipher);
+        }
+    }
+}
+
+mod wrap {
+    openssl_key_wrap_benches!(aes_128_kw, openssl_wrap_bench,
+                              Cipher::aes_128_wrap());
+    openssl_key_wrap_benches!(aes_256_kw, openssl_wrap_bench,
+                              Cipher::aes_256_wrap());
+    openssl_key_wrap_benches!(aes_128_kwp, openssl_wrap_bench,
+                              Cipher::aes_128_wrap_pad());
+    openssl_key_wrap_benches!(aes_256_kwp, openssl_wrap_bench,
+                              Cipher::aes_256_wrap_pad());
+}
+
+mod unwrap {
+    openssl_key_wrap_benches!(aes_128_kw, openssl_unwrap_bench,
+                              Cipher::aes_128_wrap());
+    openssl_key_wrap_benches!(aes_256_kw, openssl_unwrap_bench,
+                              Cipher::aes_256_wrap());
+    openssl_key_wrap_benches!(aes_128_kwp, openssl_unwrap_bench,
+                              Cipher::aes_128_wrap_pad());
+    openssl_key_wrap_benches!(aes_256_kwp, openssl_unwrap_bench,
+                              Cipher::aes_256_wrap_pad());
+}
@@ -0,0 +1,57 @@
+// GMAC is AES-GCM with an empty plaintext, where the input is the additional
+// data, so it reuses `aead::seal_in_place` with room for just the tag.
+mod gmac {
+    macro_rules! openssl_gmac_benches {
+        ( $name:ident, $cipher:expr ) => {
+            mod $name {
+                use aead::{generate_key, seal_in_place, TAG_LEN};
+                use crypto_bench;
+                use openssl::cipher::Cipher;
+                use openssl::cipher_ctx::CipherCtx;
+
+                mac_benches!(ctx, {
+                    let key = generate_key($cipher);
+                    let mut ctx = CipherCtx::new().unwrap();
+                    ctx.encrypt_init(Some($cipher), Some(&key), None)
+                       .unwrap();
+                    ctx
+                }, input, {
+                    let mut tag = [0u8; TAG_LEN];
+                    seal_in_place(&mut ctx, &crypto_bench::aead::NONCE, input,
+                                  &mut tag).unwrap();
+                });
+            }
+        }
+    }
+
+    openssl_gmac_benches!(aes_128, Cipher::aes_128_gcm());
+    openssl_gmac_benches!(aes_256, Cipher::aes_256_gcm());
+}
+
+// The `PKey` holds the expanded key, but a new `Signer` is set up for every
+// message, as with Poly1305.
+mod cmac {
+    macro_rules! openssl_cmac_benches {
+        ( $name:ident, $cipher:expr ) => {
+            mod $name {
+                use openssl::pkey::PKey;
+                use openssl::rand;
+                use openssl::sign::Signer;
+                use openssl::symm::Cipher;
+
+                mac_benches!(key, {
+                    let mut key = vec![0u8; $cipher.key_len()];
+                    rand::rand_bytes(&mut key).unwrap();
+                    PKey::cmac(&$cipher, &key).unwrap()
+                }, input, {
+                    let mut tag = [0u8; 16];
+                    let mut signer = Signer::new_without_digest(&key).unwrap();
+                    signer.sign_oneshot(&mut tag, input).unwrap();
+                });
+            }
+        }
+    }
+
+    openssl_cmac_benches!(aes_128, Cipher::aes_128_cbc());
+    openssl_cmac_benches!(aes_256, Cipher::aes_256_cbc());
+}
@@ -0,0 +1,42 @@
+use aead::{generate_key, seal_in_place, TAG_LEN};
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+
+// Only an encryption context, unlike `record_layer::Aead`.
+pub struct SealingKey {
+    ctx: CipherCtx,
+}
+
+impl SealingKey {
+    pub fn new(cipher: &'static CipherRef) -> SealingKey {
+        let key = generate_key(cipher);
+        let mut ctx = CipherCtx::new().unwrap();
+        ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+        SealingKey { ctx: ctx }
+    }
+}
+
+impl crypto_bench::many_keys::SealingKey for SealingKey {
+    fn tag_len(&self) -> usize { TAG_LEN }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        seal_in_place(&mut self.ctx, nonce, ad, in_out).map_err(|_| ())
+    }
+}
+
+macro_rules! openssl_many_keys_benches {
+    ( $name:ident, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use super::SealingKey;
+
+            many_keys_benches!(SealingKey::new($cipher));
+        }
+    }
+}
+
+openssl_many_keys_benches!(aes_128_gcm, Cipher::aes_128_gcm());
+openssl_many_keys_benches!(aes_256_gcm, Cipher::aes_256_gcm());
+openssl_many_keys_benches!(chacha20_poly1305, Cipher::chacha20_poly1305());
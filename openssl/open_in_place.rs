@@ -0,0 +1,129 @@
+// Opening in place overwrites the ciphertext with the plaintext, so the
+// ciphertext has to be restored before every open. `test::Bencher` would time
+// that copy too, so these benchmarks use Criterion's `iter_batched_ref`, which
+// copies a batch of ciphertexts before timing the opens that overwrite them.
+
+#[macro_use]
+extern crate criterion;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate crypto_bench_openssl;
+extern crate openssl;
+
+use criterion::{BatchSize, Criterion, Throughput};
+use crypto_bench_openssl::{aead, ccm, siv};
+use openssl::cipher::{Cipher, CipherRef};
+use openssl::cipher_ctx::CipherCtx;
+
+// Registers the benchmarks of opening `sealed`, which is `chunk_len` bytes of
+// ciphertext followed by a `tag_len`-byte tag, and each tampered copy of it.
+// `open` opens a record in place, with the given additional data, and returns
+// whether it was authentic.
+fn open_in_place_benches<F>(c: &mut Criterion, group_name: String,
+                            chunk_len: usize, ad: &[u8], tag_len: usize,
+                            sealed: Vec<u8>, mut open: F)
+                            where F: FnMut(&[u8], &mut [u8]) -> bool {
+    let mut group = c.benchmark_group(group_name);
+    for &tampering in crypto_bench::aead::tamperings(chunk_len) {
+        let mut ciphertext = sealed.clone();
+        let mut ad = ad.to_vec();
+        {
+            let (body, tag) = ciphertext.split_at_mut(chunk_len);
+            crypto_bench::aead::tamper(tampering, &mut ad, body, tag);
+        }
+
+        let counts = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                         tag_len);
+        let should_open = tampering == crypto_bench::aead::Tampering::None;
+        for (bench_name, counter) in
+                crypto_bench::aead::open_benches(tampering, chunk_len) {
+            group.throughput(Throughput::Bytes(counts.bytes(counter)));
+            group.bench_function(bench_name, |b| {
+                b.iter_batched_ref(|| ciphertext.clone(), |in_out| {
+                    assert_eq!(open(&ad, in_out), should_open);
+                }, BatchSize::SmallInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+fn aead_bench(c: &mut Criterion, name: &str, cipher: &'static CipherRef,
+              scenario: &str, chunk_len: usize, ad: &[u8]) {
+    let key = aead::generate_key(cipher);
+
+    let mut sealed = vec![0u8; chunk_len + aead::TAG_LEN];
+    let mut sealing_ctx = CipherCtx::new().unwrap();
+    sealing_ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+    aead::seal_in_place(&mut sealing_ctx, &crypto_bench::aead::NONCE, ad,
+                        &mut sealed).unwrap();
+
+    let mut ctx = CipherCtx::new().unwrap();
+    ctx.decrypt_init(Some(cipher), Some(&key), None).unwrap();
+
+    open_in_place_benches(
+        c, format!("aead::open_in_place::{}::{}", name, scenario), chunk_len,
+        ad, aead::TAG_LEN, sealed, |ad, in_out| {
+            aead::open_in_place(&mut ctx, &crypto_bench::aead::NONCE, ad,
+                                in_out).is_ok()
+        });
+}
+
+fn ccm_bench(c: &mut Criterion, name: &str,
+             lengths: crypto_bench::aead::Lengths, scenario: &str,
+             chunk_len: usize, ad: &[u8]) {
+    let (mut sealing_ctx, mut ctx) =
+        ccm::new_keys(Cipher::aes_128_ccm(), lengths);
+
+    let mut sealed = vec![0u8; chunk_len + lengths.tag];
+    ccm::seal_in_place(&mut sealing_ctx, lengths.nonce(), ad, &mut sealed,
+                       lengths.tag).unwrap();
+
+    open_in_place_benches(
+        c, format!("ccm::open_in_place::{}::{}", name, scenario), chunk_len,
+        ad, lengths.tag, sealed, |ad, in_out| {
+            ccm::open_in_place(&mut ctx, lengths.nonce(), ad, in_out,
+                               lengths.tag).is_ok()
+        });
+}
+
+fn siv_bench(c: &mut Criterion, name: &str, algorithm: &str, scenario: &str,
+             chunk_len: usize, ad: &[u8]) {
+    let (sealing_key, key) = siv::new_keys(algorithm);
+    let mut ctx = CipherCtx::new().unwrap();
+
+    let mut sealed = vec![0u8; chunk_len + siv::TAG_LEN];
+    siv::seal_in_place(&sealing_key, &mut ctx, &crypto_bench::aead::NONCE,
+                       ad, &mut sealed).unwrap();
+
+    open_in_place_benches(
+        c, format!("siv::open_in_place::{}::{}", name, scenario), chunk_len,
+        ad, siv::TAG_LEN, sealed, |ad, in_out| {
+            siv::open_in_place(&key, &mut ctx, &crypto_bench::aead::NONCE, ad,
+                               in_out).is_ok()
+        });
+}
+
+fn open_in_place(c: &mut Criterion) {
+    ad_only_aead_scenarios!(aead_scenario_call, aead_bench, c, "aes_128_gcm",
+                            Cipher::aes_128_gcm());
+    ad_only_aead_scenarios!(aead_scenario_call, aead_bench, c, "aes_256_gcm",
+                            Cipher::aes_256_gcm());
+    ad_only_aead_scenarios!(aead_scenario_call, aead_bench, c,
+                            "chacha20_poly1305", Cipher::chacha20_poly1305());
+
+    ad_only_aead_scenarios!(aead_scenario_call, ccm_bench, c, "aes_128_ccm",
+                            crypto_bench::aead::AES_CCM);
+    ad_only_aead_scenarios!(aead_scenario_call, ccm_bench, c,
+                            "aes_128_ccm_8", crypto_bench::aead::AES_CCM_8);
+
+    long_message_aead_scenarios!(aead_scenario_call, siv_bench, c,
+                                 "aes_128_siv", "AES-128-SIV");
+    long_message_aead_scenarios!(aead_scenario_call, siv_bench, c,
+                                 "aes_256_siv", "AES-256-SIV");
+}
+
+criterion_group!(benches, open_in_place);
+criterion_main!(benches);
@@ -7,14 +7,30 @@ extern crate crypto_bench;
 
 extern crate openssl;
 
+pub mod aead;
+mod agreement;
+mod aes;
+mod cbc_hmac;
+pub mod ccm;
+mod chacha20;
+mod ctr;
+mod key_wrap;
+mod mac;
+pub mod many_keys;
+mod poly1305;
+mod record_layer;
+pub mod siv;
+mod stream;
+mod xts;
+
 macro_rules! openssl_digest_benches {
     ( $name:ident, $block_len:expr, $alg:expr) => {
         mod $name {
             use crypto_bench;
-            use openssl::crypto::hash;
+            use openssl::hash;
 
             digest_benches!($block_len, input, {
-                let _ = hash::hash($alg, input);
+                let _ = hash::hash($alg, input).unwrap();
             });
         }
     }
@@ -22,26 +38,89 @@ macro_rules! openssl_digest_benches {
 
 mod digest {
     openssl_digest_benches!(sha1, crypto_bench::SHA1_BLOCK_LEN,
-                            hash::Type::SHA1);
+                            hash::MessageDigest::sha1());
     openssl_digest_benches!(sha256, crypto_bench::SHA256_BLOCK_LEN,
-                            hash::Type::SHA256);
+                            hash::MessageDigest::sha256());
     openssl_digest_benches!(sha384, crypto_bench::SHA384_BLOCK_LEN,
-                            hash::Type::SHA384);
+                            hash::MessageDigest::sha384());
     openssl_digest_benches!(sha512, crypto_bench::SHA512_BLOCK_LEN,
-                            hash::Type::SHA512);
+                            hash::MessageDigest::sha512());
 }
 
 mod pbkdf2 {
-    use crypto_bench;
-    use openssl;
-    use test;
-
-    pbkdf2_bench!(hmac_sha1, 20, out, {
-        let vec = openssl::crypto::pkcs5::pbkdf2_hmac_sha1(
-                    &crypto_bench::pbkdf2::PASSWORD_STR, &crypto_bench::pbkdf2::SALT,
-                    crypto_bench::pbkdf2::ITERATIONS as usize, out.len());
-        for i in 0..out.len() {
-            out[i] = vec[i];
+    macro_rules! openssl_pbkdf2_benches {
+        ( $name:ident, $output_len:expr, $digest:expr) => {
+            mod $name {
+                use crypto_bench;
+                use openssl::hash::MessageDigest;
+                use openssl::pkcs5;
+
+                pbkdf2_benches!($output_len, out,
+                                pkcs5::pbkdf2_hmac(
+                                    crypto_bench::pbkdf2::PASSWORD,
+                                    crypto_bench::pbkdf2::SALT,
+                                    crypto_bench::pbkdf2::ITERATIONS as usize,
+                                    $digest, &mut out).unwrap());
+            }
         }
-    });
+    }
+
+    openssl_pbkdf2_benches!(hmac_sha1, crypto_bench::SHA1_OUTPUT_LEN,
+                            MessageDigest::sha1());
+    openssl_pbkdf2_benches!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN,
+                            MessageDigest::sha256());
+    openssl_pbkdf2_benches!(hmac_sha384, crypto_bench::SHA384_OUTPUT_LEN,
+                            MessageDigest::sha384());
+    openssl_pbkdf2_benches!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN,
+                            MessageDigest::sha512());
+}
+
+mod password_hash {
+    // OpenSSL doesn't implement bcrypt, and rust-openssl only exposes Argon2
+    // for OpenSSL 3.2 and later.
+
+    mod scrypt {
+        use crypto_bench;
+        use openssl::pkcs5;
+
+        scrypt_benches!(log_n, r, p, out,
+                        pkcs5::scrypt(crypto_bench::password_hash::PASSWORD,
+                                      crypto_bench::password_hash::SALT,
+                                      1 << log_n, r as u64, p as u64,
+                                      // Don't let OpenSSL's default memory
+                                      // limit (32MiB) get in the way.
+                                      1 << 30, &mut out).unwrap());
+    }
+}
+
+mod tls12_prf {
+    macro_rules! openssl_tls12_prf_benches {
+        ( $name:ident, $digest:expr, $test_vector:expr) => {
+            mod $name {
+                use crypto_bench;
+                use openssl::hash::MessageDigest;
+                use openssl::pkey::PKey;
+                use openssl::sign::Signer;
+
+                tls12_prf_benches!($test_vector, secret, label, seed, out, {
+                    let key = PKey::hmac(secret).unwrap();
+                    crypto_bench::tls12_prf::p_hash(
+                        &mut out, $digest.size(), label, seed,
+                        |parts, hmac_out| {
+                            let mut signer = Signer::new($digest, &key)
+                                                 .unwrap();
+                            for part in parts {
+                                signer.update(part).unwrap();
+                            }
+                            signer.sign(hmac_out).unwrap();
+                        });
+                });
+            }
+        }
+    }
+
+    openssl_tls12_prf_benches!(p_sha256, MessageDigest::sha256(),
+                               crypto_bench::tls12_prf::P_SHA256_TEST_VECTOR);
+    openssl_tls12_prf_benches!(p_sha384, MessageDigest::sha384(),
+                               crypto_bench::tls12_prf::P_SHA384_TEST_VECTOR);
 }
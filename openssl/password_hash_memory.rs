@@ -0,0 +1,18 @@
+// Prints the peak memory use of OpenSSL's scrypt for each set of parameters
+// that is benchmarked. See `crypto_bench::peak_memory`.
+
+#[macro_use]
+extern crate crypto_bench;
+extern crate openssl;
+
+use openssl::pkcs5;
+
+fn main() {
+    scrypt_params!(scrypt_peak_memory, "openssl", log_n, r, p, out,
+                   pkcs5::scrypt(crypto_bench::password_hash::PASSWORD,
+                                 crypto_bench::password_hash::SALT,
+                                 1 << log_n, r as u64, p as u64,
+                                 // Don't let OpenSSL's default memory limit
+                                 // (32MiB) get in the way.
+                                 1 << 30, &mut out).unwrap());
+}
@@ -0,0 +1,55 @@
+use crypto_bench::poly1305::{KEY_LEN, TAG_LEN, UPDATE_LEN};
+use openssl::pkey::{Id, PKey, Private};
+use openssl::rand;
+use openssl::sign::Signer;
+use test;
+
+// Poly1305 keys are one-time keys, so a new `Signer` is set up for every
+// message. The `PKey` is reused, though.
+fn generate_key() -> PKey<Private> {
+    let mut key = [0u8; KEY_LEN];
+    rand::rand_bytes(&mut key).unwrap();
+    PKey::private_key_from_raw_bytes(&key, Id::POLY1305).unwrap()
+}
+
+fn one_shot_bench(len: usize, b: &mut test::Bencher) {
+    let key = generate_key();
+    let input = vec![0u8; len];
+    let mut tag = [0u8; TAG_LEN];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut signer = Signer::new_without_digest(&key).unwrap();
+        signer.sign_oneshot(&mut tag, &input).unwrap();
+    });
+}
+
+fn incremental_bench(len: usize, b: &mut test::Bencher) {
+    let key = generate_key();
+    let input = vec![0u8; len];
+    let mut tag = [0u8; TAG_LEN];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut signer = Signer::new_without_digest(&key).unwrap();
+        for piece in input.chunks(UPDATE_LEN) {
+            signer.update(piece).unwrap();
+        }
+        signer.sign(&mut tag).unwrap();
+    });
+}
+
+macro_rules! openssl_poly1305_bench {
+    ( $benchmark_name:ident, $len:expr, $f:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            $f($len, b);
+        }
+    }
+}
+
+mod one_shot {
+    aead_part_benches!(openssl_poly1305_bench, super::one_shot_bench);
+}
+
+mod incremental {
+    aead_part_benches!(openssl_poly1305_bench, super::incremental_bench);
+}
@@ -0,0 +1,63 @@
+use aead::{generate_key, open_in_place, seal_in_place, TAG_LEN};
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+
+pub struct Aead {
+    sealing_ctx: CipherCtx,
+    opening_ctx: CipherCtx,
+}
+
+impl Aead {
+    pub fn new(cipher: &'static CipherRef) -> Aead {
+        let key = generate_key(cipher);
+        let mut sealing_ctx = CipherCtx::new().unwrap();
+        sealing_ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+        let mut opening_ctx = CipherCtx::new().unwrap();
+        opening_ctx.decrypt_init(Some(cipher), Some(&key), None).unwrap();
+        Aead { sealing_ctx: sealing_ctx, opening_ctx: opening_ctx }
+    }
+}
+
+impl crypto_bench::record_layer::Aead for Aead {
+    fn tag_len(&self) -> usize { TAG_LEN }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        seal_in_place(&mut self.sealing_ctx, nonce, ad, in_out).map_err(|_| ())
+    }
+
+    fn open_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        open_in_place(&mut self.opening_ctx, nonce, ad, in_out).map_err(|_| ())
+    }
+}
+
+macro_rules! openssl_record_layer_benches {
+    ( $name:ident, $bench:ident, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use super::super::Aead;
+
+            record_layer_benches!($bench, Aead::new($cipher));
+        }
+    }
+}
+
+mod seal {
+    openssl_record_layer_benches!(aes_128_gcm, record_layer_seal_bench,
+                                  Cipher::aes_128_gcm());
+    openssl_record_layer_benches!(aes_256_gcm, record_layer_seal_bench,
+                                  Cipher::aes_256_gcm());
+    openssl_record_layer_benches!(chacha20_poly1305, record_layer_seal_bench,
+                                  Cipher::chacha20_poly1305());
+}
+
+mod open {
+    openssl_record_layer_benches!(aes_128_gcm, record_layer_open_bench,
+                                  Cipher::aes_128_gcm());
+    openssl_record_layer_benches!(aes_256_gcm, record_layer_open_bench,
+                                  Cipher::aes_256_gcm());
+    openssl_record_layer_benches!(chacha20_poly1305, record_layer_open_bench,
+                                  Cipher::chacha20_poly1305());
+}
@@ -0,0 +1,84 @@
+// AES-SIV (RFC 5297), used as a nonce-based AEAD: the nonce is the last
+// component of the additional data. Since AES-SIV is resistant to nonce misuse,
+// reusing `crypto_bench::aead::NONCE` is OK here.
+
+use crypto_bench;
+use openssl::cipher::Cipher;
+use openssl::cipher_ctx::CipherCtx;
+use openssl::error::ErrorStack;
+use openssl::rand;
+use test;
+
+pub const TAG_LEN: usize = 128 / 8;
+
+// OpenSSL's AES-SIV contexts can't be reinitialized for a new message without
+// setting the key again. Instead, a context that has the key set is copied for
+// each message, so that the key schedule is reused.
+pub fn new_keys(algorithm: &str) -> (CipherCtx, CipherCtx) {
+    let cipher = Cipher::fetch(None, algorithm, None).unwrap();
+    let mut key = vec![0u8; cipher.key_length()];
+    rand::rand_bytes(&mut key).unwrap();
+
+    let mut sealing_key = CipherCtx::new().unwrap();
+    sealing_key.encrypt_init(Some(&cipher), Some(&key), None).unwrap();
+    let mut opening_key = CipherCtx::new().unwrap();
+    opening_key.decrypt_init(Some(&cipher), Some(&key), None).unwrap();
+    (sealing_key, opening_key)
+}
+
+// `in_out` is the plaintext followed by room for the tag. `ctx` is
+// overwritten with a copy of `key`.
+pub fn seal_in_place(key: &CipherCtx, ctx: &mut CipherCtx, nonce: &[u8],
+                     ad: &[u8], in_out: &mut [u8])
+                     -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - TAG_LEN;
+    try!(ctx.copy(key));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update(nonce, None));
+    try!(ctx.cipher_update_inplace(&mut in_out[..chunk_len], chunk_len));
+    try!(ctx.cipher_final(&mut []));
+    ctx.tag(&mut in_out[chunk_len..])
+}
+
+// `in_out` is the ciphertext followed by the tag. `ctx` is overwritten with a
+// copy of `key`.
+pub fn open_in_place(key: &CipherCtx, ctx: &mut CipherCtx, nonce: &[u8],
+                     ad: &[u8], in_out: &mut [u8])
+                     -> Result<(), ErrorStack> {
+    let chunk_len = in_out.len() - TAG_LEN;
+    let (in_out, tag) = in_out.split_at_mut(chunk_len);
+    try!(ctx.copy(key));
+    // The tag is the synthetic IV, so it is needed before decrypting.
+    try!(ctx.set_tag(tag));
+    try!(ctx.cipher_update(ad, None));
+    try!(ctx.cipher_update(nonce, None));
+    try!(ctx.cipher_update_inplace(in_out, chunk_len));
+    try!(ctx.cipher_final(&mut []));
+    Ok(())
+}
+
+fn seal_in_place_bench(algorithm: &str, chunk_len: usize, ad: &[u8],
+                       counter: crypto_bench::aead::Counter,
+                       b: &mut test::Bencher) {
+    let (key, _) = new_keys(algorithm);
+    let mut ctx = CipherCtx::new().unwrap();
+
+    let mut in_out = vec![0u8; chunk_len + TAG_LEN];
+
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                  TAG_LEN).bytes(counter);
+
+    b.iter(|| {
+        seal_in_place(&key, &mut ctx, &crypto_bench::aead::NONCE, ad,
+                      &mut in_out).unwrap();
+    });
+}
+
+mod seal_in_place {
+    use super::seal_in_place_bench;
+
+    long_message_aead_benches!(aes_128_siv, aead_seal_in_place_bench,
+                               seal_in_place_bench, "AES-128-SIV");
+    long_message_aead_benches!(aes_256_siv, aead_seal_in_place_bench,
+                               seal_in_place_bench, "AES-256-SIV");
+}
@@ -0,0 +1,31 @@
+// The STREAM benchmarks use the same `crypto_bench::record_layer::Aead` as the
+// record layer benchmarks.
+
+macro_rules! openssl_stream_benches {
+    ( $name:ident, $bench:ident, $cipher:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+            use record_layer::Aead;
+
+            stream_benches!($bench, Aead::new($cipher));
+        }
+    }
+}
+
+mod seal {
+    openssl_stream_benches!(aes_128_gcm, stream_seal_bench,
+                            Cipher::aes_128_gcm());
+    openssl_stream_benches!(aes_256_gcm, stream_seal_bench,
+                            Cipher::aes_256_gcm());
+    openssl_stream_benches!(chacha20_poly1305, stream_seal_bench,
+                            Cipher::chacha20_poly1305());
+}
+
+mod open {
+    openssl_stream_benches!(aes_128_gcm, stream_open_bench,
+                            Cipher::aes_128_gcm());
+    openssl_stream_benches!(aes_256_gcm, stream_open_bench,
+                            Cipher::aes_256_gcm());
+    openssl_stream_benches!(chacha20_poly1305, stream_open_bench,
+                            Cipher::chacha20_poly1305());
+}
@@ -0,0 +1,65 @@
+// AES-128-XTS and AES-256-XTS take two AES keys, so their keys are 256 and
+// 512 bits long.
+
+use crypto_bench;
+use openssl::cipher::CipherRef;
+use openssl::cipher_ctx::CipherCtx;
+use test;
+
+use aead::generate_key;
+
+// Each sector is a separate XTS message, started by setting its tweak as the
+// IV. The key schedule is reused.
+fn xts_bench(len: usize, sector_len: usize, cipher: &CipherRef,
+             encrypt: bool, b: &mut test::Bencher) {
+    let key = generate_key(cipher);
+    let mut ctx = CipherCtx::new().unwrap();
+    if encrypt {
+        ctx.encrypt_init(Some(cipher), Some(&key), None).unwrap();
+    } else {
+        ctx.decrypt_init(Some(cipher), Some(&key), None).unwrap();
+    }
+    let mut in_out = vec![0u8; len];
+    b.bytes = len as u64;
+    b.iter(|| {
+        for (sector, in_out) in in_out.chunks_mut(sector_len).enumerate() {
+            let tweak = crypto_bench::xts::tweak(sector as u64);
+            if encrypt {
+                ctx.encrypt_init(None, None, Some(&tweak)).unwrap();
+            } else {
+                ctx.decrypt_init(None, None, Some(&tweak)).unwrap();
+            }
+            ctx.cipher_update_inplace(in_out, sector_len).unwrap();
+        }
+    });
+}
+
+macro_rules! openssl_xts_bench {
+    ( $benchmark_name:ident, $len:expr, $sector_len:expr, $cipher:expr,
+      $encrypt:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::super::xts_bench($len, $sector_len, $cipher, $encrypt, b);
+        }
+    }
+}
+
+macro_rules! openssl_xts_benches {
+    ( $name:ident, $cipher:expr, $encrypt:expr ) => {
+        mod $name {
+            use openssl::cipher::Cipher;
+
+            xts_benches!(openssl_xts_bench, $cipher, $encrypt);
+        }
+    }
+}
+
+mod encrypt {
+    openssl_xts_benches!(aes_128, Cipher::aes_128_xts(), true);
+    openssl_xts_benches!(aes_256, Cipher::aes_256_xts(), true);
+}
+
+mod decrypt {
+    openssl_xts_benches!(aes_128, Cipher::aes_128_xts(), false);
+    openssl_xts_benches!(aes_256, Cipher::aes_256_xts(), false);
+}
@@ -3,27 +3,35 @@
 
 use crypto_bench;
 use ring::aead;
-use ring::rand::SecureRandom;
+use ring::rand::{SecureRandom, SystemRandom};
 use test;
 
-fn generate_sealing_key(algorithm: &'static aead::Algorithm, rng: &SecureRandom)
-                        -> Result<aead::SealingKey, ()> {
+pub fn generate_key_bytes(algorithm: &'static aead::Algorithm,
+                          rng: &SecureRandom) -> Result<Vec<u8>, ()> {
     let mut key_bytes = vec![0u8; algorithm.key_len()];
     try!(rng.fill(&mut key_bytes));
+    Ok(key_bytes)
+}
+
+pub fn generate_sealing_key(algorithm: &'static aead::Algorithm,
+                            rng: &SecureRandom)
+                            -> Result<aead::SealingKey, ()> {
+    let key_bytes = try!(generate_key_bytes(algorithm, rng));
     aead::SealingKey::new(algorithm, &key_bytes)
 }
 
 fn seal_in_place_bench(algorithm: &'static aead::Algorithm,
-                       rng: &SecureRandom,
                        chunk_len: usize, ad: &[u8],
+                       counter: crypto_bench::aead::Counter,
                        b: &mut test::Bencher) {
     let out_suffix_capacity = algorithm.max_overhead_len();
     let mut in_out = vec![0u8; chunk_len + out_suffix_capacity];
 
-    // XXX: This is a little misleading when `ad` isn't empty.
-    b.bytes = chunk_len as u64;
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                  out_suffix_capacity)
+        .bytes(counter);
 
-    let key = generate_sealing_key(algorithm, rng).unwrap();
+    let key = generate_sealing_key(algorithm, &SystemRandom::new()).unwrap();
     b.iter(|| {
         aead::seal_in_place(&key, &crypto_bench::aead::NONCE,
                             &mut in_out, out_suffix_capacity,
@@ -31,57 +39,90 @@ fn seal_in_place_bench(algorithm: &'static aead::Algorithm,
     });
 }
 
-macro_rules! ring_seal_in_place_bench {
-    ( $benchmark_name:ident, $algorithm:expr, $chunk_len:expr, $ad:expr ) => {
-        #[bench]
-        fn $benchmark_name(b: &mut test::Bencher) {
-            use ring::aead;
-            use ring::rand::SystemRandom;
-            use super::super::seal_in_place_bench;
-            let rng = SystemRandom::new();
-            seal_in_place_bench($algorithm, &rng, $chunk_len, $ad, b);
-        }
-    }
+fn seal_records_bench(algorithm: &'static aead::Algorithm,
+                      version: crypto_bench::aead::TlsVersion,
+                      chunk_len: usize, counter: crypto_bench::aead::Counter,
+                      b: &mut test::Bencher) {
+    let out_suffix_capacity = algorithm.max_overhead_len();
+    let mut in_out = vec![0u8; chunk_len + out_suffix_capacity];
+
+    let mut records = crypto_bench::aead::Records::new(version, chunk_len);
+    b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len,
+                                                  records.ad().len(),
+                                                  out_suffix_capacity)
+        .bytes(counter);
+
+    let key = generate_sealing_key(algorithm, &SystemRandom::new()).unwrap();
+    b.iter(|| {
+        records.advance();
+        aead::seal_in_place(&key, records.nonce(), &mut in_out,
+                            out_suffix_capacity, records.ad()).unwrap();
+    });
 }
 
-macro_rules! ring_seal_in_place_benches {
+// Key setup is done once per connection, not once per record, so the
+// `seal_in_place` and `open_in_place` benchmarks exclude it. The
+// `open_in_place` benchmarks are Criterion benchmarks, in `open_in_place.rs`.
+macro_rules! ring_key_setup_benches {
     ( $name:ident, $algorithm:expr ) => {
         mod $name {
-            use crypto_bench;
+            use ring::aead;
+            use ring::rand::SystemRandom;
             use test;
 
-            // A TLS 1.2 finished message.
-            ring_seal_in_place_bench!(tls12_finished, $algorithm,
-                                      crypto_bench::aead::TLS12_FINISHED_LEN,
-                                      &crypto_bench::aead::TLS12_AD);
-            ring_seal_in_place_bench!(tls13_finished, $algorithm,
-                                      crypto_bench::aead::TLS13_FINISHED_LEN,
-                                      &crypto_bench::aead::TLS13_AD);
-
-            // For comparison with BoringSSL.
-            ring_seal_in_place_bench!(tls12_16, $algorithm, 16,
-                                      &crypto_bench::aead::TLS12_AD);
-
-            // ~1 packet of data in TLS.
-            ring_seal_in_place_bench!(tls12_1350, $algorithm, 1350,
-                                      &crypto_bench::aead::TLS12_AD);
-            ring_seal_in_place_bench!(tls13_1350, $algorithm, 1350,
-                                      &crypto_bench::aead::TLS13_AD);
-
-            // For comparison with BoringSSL.
-            ring_seal_in_place_bench!(tls12_8192, $algorithm, 8192,
-                                      &crypto_bench::aead::TLS12_AD);
-            ring_seal_in_place_bench!(tls13_8192, $algorithm, 8192,
-                                      &crypto_bench::aead::TLS13_AD);
+            #[bench]
+            fn sealing(b: &mut test::Bencher) {
+                let rng = SystemRandom::new();
+                let key_bytes =
+                    super::super::generate_key_bytes($algorithm, &rng).unwrap();
+                b.iter(|| {
+                    aead::SealingKey::new($algorithm, &key_bytes).unwrap()
+                });
+            }
+
+            #[bench]
+            fn opening(b: &mut test::Bencher) {
+                let rng = SystemRandom::new();
+                let key_bytes =
+                    super::super::generate_key_bytes($algorithm, &rng).unwrap();
+                b.iter(|| {
+                    aead::OpeningKey::new($algorithm, &key_bytes).unwrap()
+                });
+            }
         }
     }
 }
 
+mod key_setup {
+    ring_key_setup_benches!(aes_128_gcm, &aead::AES_128_GCM);
+    ring_key_setup_benches!(aes_256_gcm, &aead::AES_256_GCM);
+    ring_key_setup_benches!(chacha20_poly1305, &aead::CHACHA20_POLY1305);
+    ring_key_setup_benches!(chacha20_poly1305_old,
+                            &aead::CHACHA20_POLY1305_OLD);
+}
+
 mod seal_in_place {
-    ring_seal_in_place_benches!(aes_128_gcm, &aead::AES_128_GCM);
-    ring_seal_in_place_benches!(aes_256_gcm, &aead::AES_256_GCM);
-    ring_seal_in_place_benches!(chacha20_poly1305,
-                                &aead::CHACHA20_POLY1305);
-    ring_seal_in_place_benches!(chacha20_poly1305_old,
-                                &aead::CHACHA20_POLY1305_OLD);
+    use super::seal_in_place_bench;
+
+    aead_benches!(aes_128_gcm, aead_seal_in_place_bench, seal_in_place_bench,
+                  &::ring::aead::AES_128_GCM);
+    aead_benches!(aes_256_gcm, aead_seal_in_place_bench, seal_in_place_bench,
+                  &::ring::aead::AES_256_GCM);
+    aead_benches!(chacha20_poly1305, aead_seal_in_place_bench,
+                  seal_in_place_bench, &::ring::aead::CHACHA20_POLY1305);
+    aead_benches!(chacha20_poly1305_old, aead_seal_in_place_bench,
+                  seal_in_place_bench, &::ring::aead::CHACHA20_POLY1305_OLD);
+}
+
+mod seal_records {
+    use super::seal_records_bench;
+
+    aead_seal_records_benches!(aes_128_gcm, seal_records_bench,
+                               &::ring::aead::AES_128_GCM);
+    aead_seal_records_benches!(aes_256_gcm, seal_records_bench,
+                               &::ring::aead::AES_256_GCM);
+    aead_seal_records_benches!(chacha20_poly1305, seal_records_bench,
+                               &::ring::aead::CHACHA20_POLY1305);
+    aead_seal_records_benches!(chacha20_poly1305_old, seal_records_bench,
+                               &::ring::aead::CHACHA20_POLY1305_OLD);
 }
@@ -0,0 +1,49 @@
+// `agreement::agree_ephemeral` consumes the private key, so a new one has to
+// be generated for every agreement. `test::Bencher` would time that too, so
+// these benchmarks use Criterion's `iter_batched`, which generates a batch of
+// private keys before timing the agreements that consume them.
+
+#[macro_use]
+extern crate criterion;
+
+extern crate ring;
+
+use criterion::{BatchSize, Criterion};
+use ring::{agreement, rand};
+use ring::input::Input;
+
+fn agree_ephemeral(c: &mut Criterion, name: &str,
+                   alg: &'static agreement::Algorithm) {
+    let rng = rand::SystemRandom::new();
+
+    // These operations are done by the peer.
+    let b_private = agreement::EphemeralPrivateKey::generate(alg, &rng)
+                        .unwrap();
+    let mut b_public = [0; agreement::PUBLIC_KEY_MAX_LEN];
+    let b_public = &mut b_public[..b_private.public_key_len()];
+    b_private.compute_public_key(b_public).unwrap();
+    let b_public = &b_public[..];
+
+    c.bench_function(name, |b| {
+        b.iter_batched(|| {
+            agreement::EphemeralPrivateKey::generate(alg, &rng).unwrap()
+        }, |a_private| {
+            let b_public = Input::new(b_public).unwrap();
+            agreement::agree_ephemeral(a_private, alg, b_public, (), |_| {
+                Ok(())
+            }).unwrap();
+        }, BatchSize::SmallInput)
+    });
+}
+
+fn agreement(c: &mut Criterion) {
+    agree_ephemeral(c, "agreement::p256::agree_ephemeral",
+                    &agreement::ECDH_P256);
+    agree_ephemeral(c, "agreement::p384::agree_ephemeral",
+                    &agreement::ECDH_P384);
+    agree_ephemeral(c, "agreement::x25519::agree_ephemeral",
+                    &agreement::X25519);
+}
+
+criterion_group!(benches, agreement);
+criterion_main!(benches);
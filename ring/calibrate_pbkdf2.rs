@@ -0,0 +1,29 @@
+// Finds, for each PBKDF2 PRF, the number of iterations that *ring* can
+// compute in a given amount of time. See `../calibrate_pbkdf2`.
+
+extern crate crypto_bench;
+extern crate ring;
+
+use crypto_bench::pbkdf2::{PASSWORD, SALT};
+use crypto_bench::pbkdf2::calibrate;
+use ring::pbkdf2;
+use std::time::Duration;
+
+fn report(prf_name: &str, out_len: usize, prf: &'static pbkdf2::PRF,
+          target: Duration) {
+    calibrate::report("ring", prf_name, target, out_len, |iterations, out| {
+        pbkdf2::derive(prf, iterations as usize, SALT, PASSWORD, out)
+    });
+}
+
+fn main() {
+    let target = calibrate::target_from_args();
+    report("hmac_sha1", crypto_bench::SHA1_OUTPUT_LEN, &pbkdf2::HMAC_SHA1,
+           target);
+    report("hmac_sha256", crypto_bench::SHA256_OUTPUT_LEN,
+           &pbkdf2::HMAC_SHA256, target);
+    report("hmac_sha384", crypto_bench::SHA384_OUTPUT_LEN,
+           &pbkdf2::HMAC_SHA384, target);
+    report("hmac_sha512", crypto_bench::SHA512_OUTPUT_LEN,
+           &pbkdf2::HMAC_SHA512, target);
+}
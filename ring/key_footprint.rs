@@ -0,0 +1,23 @@
+// Reports how much memory each of *ring*'s `many_keys::SealingKey`s takes,
+// for comparison with the `many_keys` benchmarks. See `../README.md`.
+
+extern crate crypto_bench;
+extern crate crypto_bench_ring;
+extern crate ring;
+
+use crypto_bench::footprint::{self, CountingAllocator};
+use crypto_bench_ring::many_keys::SealingKey;
+use ring::aead;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn report(name: &str, algorithm: &'static aead::Algorithm) {
+    footprint::report("ring", name, || SealingKey::new(algorithm));
+}
+
+fn main() {
+    report("aes_128_gcm", &aead::AES_128_GCM);
+    report("aes_256_gcm", &aead::AES_256_GCM);
+    report("chacha20_poly1305", &aead::CHACHA20_POLY1305);
+}
@@ -0,0 +1,30 @@
+// ring exposes GMAC only as AES-GCM with an empty plaintext, where the input
+// is the additional data and the output is just the tag.
+
+macro_rules! ring_gmac_benches {
+    ( $name:ident, $algorithm:expr ) => {
+        mod $name {
+            use aead::generate_sealing_key;
+            use crypto_bench;
+            use ring::{aead, rand};
+
+            // The tag's buffer is allocated with the key, so that allocating
+            // it isn't timed.
+            mac_benches!(state, {
+                let rng = rand::SystemRandom::new();
+                (generate_sealing_key($algorithm, &rng).unwrap(),
+                 vec![0u8; $algorithm.max_overhead_len()])
+            }, input, {
+                let (ref key, ref mut tag) = state;
+                let tag_len = tag.len();
+                aead::seal_in_place(key, &crypto_bench::aead::NONCE, tag,
+                                    tag_len, input).unwrap()
+            });
+        }
+    }
+}
+
+mod gmac {
+    ring_gmac_benches!(aes_128, &aead::AES_128_GCM);
+    ring_gmac_benches!(aes_256, &aead::AES_256_GCM);
+}
@@ -0,0 +1,46 @@
+use crypto_bench;
+use ring::aead;
+use ring::rand::SystemRandom;
+
+// Only a sealing key, unlike `record_layer::Aead`. `key_footprint` measures
+// this type.
+pub struct SealingKey {
+    key: aead::SealingKey,
+    tag_len: usize,
+}
+
+impl SealingKey {
+    pub fn new(algorithm: &'static aead::Algorithm) -> SealingKey {
+        let rng = SystemRandom::new();
+        SealingKey {
+            key: super::aead::generate_sealing_key(algorithm, &rng).unwrap(),
+            tag_len: algorithm.max_overhead_len(),
+        }
+    }
+}
+
+impl crypto_bench::many_keys::SealingKey for SealingKey {
+    fn tag_len(&self) -> usize { self.tag_len }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        let _ = try!(aead::seal_in_place(&self.key, nonce, in_out,
+                                         self.tag_len, ad));
+        Ok(())
+    }
+}
+
+macro_rules! ring_many_keys_benches {
+    ( $name:ident, $algorithm:expr ) => {
+        mod $name {
+            use ring::aead;
+            use super::SealingKey;
+
+            many_keys_benches!(SealingKey::new($algorithm));
+        }
+    }
+}
+
+ring_many_keys_benches!(aes_128_gcm, &aead::AES_128_GCM);
+ring_many_keys_benches!(aes_256_gcm, &aead::AES_256_GCM);
+ring_many_keys_benches!(chacha20_poly1305, &aead::CHACHA20_POLY1305);
@@ -0,0 +1,77 @@
+// `aead::open_in_place` overwrites the ciphertext with the plaintext, so the
+// ciphertext has to be restored before every open. `test::Bencher` would time
+// that copy too, so these benchmarks use Criterion's `iter_batched_ref`, which
+// copies a batch of ciphertexts before timing the opens that overwrite them.
+
+#[macro_use]
+extern crate criterion;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate crypto_bench_ring;
+extern crate ring;
+
+use criterion::{BatchSize, Criterion, Throughput};
+use crypto_bench_ring::aead::generate_key_bytes;
+use ring::aead;
+use ring::rand::SystemRandom;
+
+fn open_in_place_bench(c: &mut Criterion, name: &str,
+                       algorithm: &'static aead::Algorithm, scenario: &str,
+                       chunk_len: usize, ad: &[u8]) {
+    let key_bytes =
+        generate_key_bytes(algorithm, &SystemRandom::new()).unwrap();
+    let sealing_key = aead::SealingKey::new(algorithm, &key_bytes).unwrap();
+    let opening_key = aead::OpeningKey::new(algorithm, &key_bytes).unwrap();
+
+    let out_suffix_capacity = algorithm.max_overhead_len();
+    let mut sealed = vec![0u8; chunk_len + out_suffix_capacity];
+    let sealed_len =
+        aead::seal_in_place(&sealing_key, &crypto_bench::aead::NONCE,
+                            &mut sealed, out_suffix_capacity, ad).unwrap();
+    sealed.truncate(sealed_len);
+
+    let mut group = c.benchmark_group(
+        format!("aead::open_in_place::{}::{}", name, scenario));
+    for &tampering in crypto_bench::aead::tamperings(chunk_len) {
+        let mut ciphertext = sealed.clone();
+        let mut ad = ad.to_vec();
+        {
+            let (body, tag) = ciphertext.split_at_mut(chunk_len);
+            crypto_bench::aead::tamper(tampering, &mut ad, body, tag);
+        }
+
+        let counts = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                         out_suffix_capacity);
+        let should_open = tampering == crypto_bench::aead::Tampering::None;
+        for (bench_name, counter) in
+                crypto_bench::aead::open_benches(tampering, chunk_len) {
+            group.throughput(Throughput::Bytes(counts.bytes(counter)));
+            group.bench_function(bench_name, |b| {
+                b.iter_batched_ref(|| ciphertext.clone(), |in_out| {
+                    let result = aead::open_in_place(&opening_key,
+                                                     &crypto_bench::aead::NONCE,
+                                                     0, in_out, &ad);
+                    assert_eq!(result.is_ok(), should_open);
+                }, BatchSize::SmallInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+fn open_in_place(c: &mut Criterion) {
+    ad_only_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                            "aes_128_gcm", &aead::AES_128_GCM);
+    ad_only_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                            "aes_256_gcm", &aead::AES_256_GCM);
+    ad_only_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                            "chacha20_poly1305", &aead::CHACHA20_POLY1305);
+    ad_only_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                            "chacha20_poly1305_old",
+                            &aead::CHACHA20_POLY1305_OLD);
+}
+
+criterion_group!(benches, open_in_place);
+criterion_main!(benches);
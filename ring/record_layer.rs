@@ -0,0 +1,69 @@
+use crypto_bench;
+use ring::aead;
+use ring::rand::SystemRandom;
+
+pub struct Aead {
+    sealing_key: aead::SealingKey,
+    opening_key: aead::OpeningKey,
+    tag_len: usize,
+}
+
+impl Aead {
+    pub fn new(algorithm: &'static aead::Algorithm) -> Aead {
+        let rng = SystemRandom::new();
+        let key_bytes =
+            super::aead::generate_key_bytes(algorithm, &rng).unwrap();
+        Aead {
+            sealing_key: aead::SealingKey::new(algorithm, &key_bytes).unwrap(),
+            opening_key: aead::OpeningKey::new(algorithm, &key_bytes).unwrap(),
+            tag_len: algorithm.max_overhead_len(),
+        }
+    }
+}
+
+impl crypto_bench::record_layer::Aead for Aead {
+    fn tag_len(&self) -> usize { self.tag_len }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        let _ = try!(aead::seal_in_place(&self.sealing_key, nonce, in_out,
+                                         self.tag_len, ad));
+        Ok(())
+    }
+
+    fn open_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        let _ = try!(aead::open_in_place(&self.opening_key, nonce, 0, in_out,
+                                         ad));
+        Ok(())
+    }
+}
+
+macro_rules! ring_record_layer_benches {
+    ( $name:ident, $bench:ident, $algorithm:expr ) => {
+        mod $name {
+            use ring::aead;
+            use super::super::Aead;
+
+            record_layer_benches!($bench, Aead::new($algorithm));
+        }
+    }
+}
+
+mod seal {
+    ring_record_layer_benches!(aes_128_gcm, record_layer_seal_bench,
+                               &aead::AES_128_GCM);
+    ring_record_layer_benches!(aes_256_gcm, record_layer_seal_bench,
+                               &aead::AES_256_GCM);
+    ring_record_layer_benches!(chacha20_poly1305, record_layer_seal_bench,
+                               &aead::CHACHA20_POLY1305);
+}
+
+mod open {
+    ring_record_layer_benches!(aes_128_gcm, record_layer_open_bench,
+                               &aead::AES_128_GCM);
+    ring_record_layer_benches!(aes_256_gcm, record_layer_open_bench,
+                               &aead::AES_256_GCM);
+    ring_record_layer_benches!(chacha20_poly1305, record_layer_open_bench,
+                               &aead::CHACHA20_POLY1305);
+}
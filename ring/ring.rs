@@ -7,7 +7,11 @@ extern crate crypto_bench;
 
 extern crate ring;
 
-mod aead;
+pub mod aead;
+mod mac;
+pub mod many_keys;
+mod record_layer;
+mod stream;
 
 mod agreement {
     macro_rules! ring_agreement_benches {
@@ -42,12 +46,9 @@ mod agreement {
                     });
                 }
 
-                // XXX: Because ring::agreement::agree_ephemeral moves its
-                // private key argument, we cannot measure
-                // `agreement::agree_ephemeral` on its own using the Rust
-                // `Bencher` interface. To get an idea of its performance,
-                // subtract the timing of `generate_private_key` from the
-                // timing of this function.
+                // `agree_ephemeral` alone, with the private key generated
+                // outside the timed region, is benchmarked in
+                // agree_ephemeral.rs.
                 #[bench]
                 fn generate_key_pair_and_agree_ephemeral(b: &mut test::Bencher) {
                     let rng = rand::SystemRandom::new();
@@ -104,19 +105,58 @@ mod digest {
 }
 
 mod pbkdf2 {
-    use crypto_bench;
-    use ring::pbkdf2;
-    use test;
-
-    pbkdf2_bench!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN, out,
-                  pbkdf2::derive(&pbkdf2::HMAC_SHA256,
-                                 crypto_bench::pbkdf2::ITERATIONS as usize,
-                                 &crypto_bench::pbkdf2::SALT,
-                                 crypto_bench::pbkdf2::PASSWORD, &mut out));
-
-    pbkdf2_bench!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN, out,
-                  pbkdf2::derive(&pbkdf2::HMAC_SHA512,
-                                 crypto_bench::pbkdf2::ITERATIONS as usize,
-                                 crypto_bench::pbkdf2::SALT,
-                                 crypto_bench::pbkdf2::PASSWORD, &mut out));
+    macro_rules! ring_pbkdf2_benches {
+        ( $name:ident, $output_len:expr, $prf:expr) => {
+            mod $name {
+                use crypto_bench;
+                use ring::pbkdf2;
+
+                pbkdf2_benches!($output_len, out,
+                                pbkdf2::derive($prf,
+                                               crypto_bench::pbkdf2::ITERATIONS
+                                                   as usize,
+                                               crypto_bench::pbkdf2::SALT,
+                                               crypto_bench::pbkdf2::PASSWORD,
+                                               &mut out));
+            }
+        }
+    }
+
+    ring_pbkdf2_benches!(hmac_sha1, crypto_bench::SHA1_OUTPUT_LEN,
+                         &pbkdf2::HMAC_SHA1);
+    ring_pbkdf2_benches!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN,
+                         &pbkdf2::HMAC_SHA256);
+    ring_pbkdf2_benches!(hmac_sha384, crypto_bench::SHA384_OUTPUT_LEN,
+                         &pbkdf2::HMAC_SHA384);
+    ring_pbkdf2_benches!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN,
+                         &pbkdf2::HMAC_SHA512);
+}
+
+mod tls12_prf {
+    macro_rules! ring_tls12_prf_benches {
+        ( $name:ident, $digest_alg:expr, $test_vector:expr) => {
+            mod $name {
+                use crypto_bench;
+                use ring::{digest, hmac};
+
+                tls12_prf_benches!($test_vector, secret, label, seed, out, {
+                    let key = hmac::SigningKey::new($digest_alg, secret);
+                    crypto_bench::tls12_prf::p_hash(
+                        &mut out, $digest_alg.output_len, label, seed,
+                        |parts, hmac_out| {
+                            let mut ctx = hmac::SigningContext::with_key(&key);
+                            for part in parts {
+                                ctx.update(part);
+                            }
+                            hmac_out.copy_from_slice(ctx.sign().as_ref());
+                        });
+                });
+            }
+        }
+    }
+
+    ring_tls12_prf_benches!(p_sha256, &digest::SHA256,
+                            crypto_bench::tls12_prf::P_SHA256_TEST_VECTOR);
+    ring_tls12_prf_benches!(p_sha384, &digest::SHA384,
+                            crypto_bench::tls12_prf::P_SHA384_TEST_VECTOR);
 }
@@ -0,0 +1,27 @@
+// The STREAM benchmarks use the same `crypto_bench::record_layer::Aead` as the
+// record layer benchmarks.
+
+macro_rules! ring_stream_benches {
+    ( $name:ident, $bench:ident, $algorithm:expr ) => {
+        mod $name {
+            use ring::aead;
+            use record_layer::Aead;
+
+            stream_benches!($bench, Aead::new($algorithm));
+        }
+    }
+}
+
+mod seal {
+    ring_stream_benches!(aes_128_gcm, stream_seal_bench, &aead::AES_128_GCM);
+    ring_stream_benches!(aes_256_gcm, stream_seal_bench, &aead::AES_256_GCM);
+    ring_stream_benches!(chacha20_poly1305, stream_seal_bench,
+                         &aead::CHACHA20_POLY1305);
+}
+
+mod open {
+    ring_stream_benches!(aes_128_gcm, stream_open_bench, &aead::AES_128_GCM);
+    ring_stream_benches!(aes_256_gcm, stream_open_bench, &aead::AES_256_GCM);
+    ring_stream_benches!(chacha20_poly1305, stream_open_bench,
+                         &aead::CHACHA20_POLY1305);
+}
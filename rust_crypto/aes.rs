@@ -0,0 +1,168 @@
+use crypto::symmetriccipher::{BlockDecryptor, BlockEncryptor};
+use crypto_bench;
+use crypto_bench::block_cipher::BLOCK_LEN;
+
+// rust-crypto's block ciphers don't work in place, so each block is copied
+// before it is encrypted or decrypted back into `in_out`. This is how
+// rust-crypto's own ECB mode works too.
+pub struct BlockCipher<E: BlockEncryptor, D: BlockDecryptor> {
+    encryptor: E,
+    decryptor: D,
+}
+
+impl<E, D> crypto_bench::block_cipher::BlockCipher for BlockCipher<E, D>
+        where E: BlockEncryptor, D: BlockDecryptor {
+    fn encrypt(&mut self, in_out: &mut [u8], len: usize) {
+        for block in in_out[..len].chunks_mut(BLOCK_LEN) {
+            let mut input = [0u8; BLOCK_LEN];
+            input.copy_from_slice(block);
+            self.encryptor.encrypt_block(&input, block);
+        }
+    }
+
+    fn decrypt(&mut self, in_out: &mut [u8], len: usize) {
+        for block in in_out[..len].chunks_mut(BLOCK_LEN) {
+            let mut input = [0u8; BLOCK_LEN];
+            input.copy_from_slice(block);
+            self.decryptor.decrypt_block(&input, block);
+        }
+    }
+}
+
+// The constant-time, bitsliced, software implementation.
+mod aessafe {
+    macro_rules! aessafe_benches {
+        ( $name:ident, $bench:ident, $key_len:expr, $encryptor:ident,
+          $decryptor:ident ) => {
+            mod $name {
+                use aead::generate_key;
+                use aes::BlockCipher;
+                use crypto::aessafe;
+
+                block_cipher_benches!($bench, {
+                    let key = generate_key($key_len);
+                    BlockCipher {
+                        encryptor: aessafe::$encryptor::new(&key),
+                        decryptor: aessafe::$decryptor::new(&key),
+                    }
+                });
+            }
+        }
+    }
+
+    macro_rules! aessafe_key_setup_bench {
+        ( $name:ident, $key_len:expr, $key_schedule:ident ) => {
+            #[bench]
+            fn $name(b: &mut test::Bencher) {
+                let key = generate_key($key_len);
+                b.iter(|| aessafe::$key_schedule::new(&key));
+            }
+        }
+    }
+
+    mod encrypt {
+        aessafe_benches!(aes_128, block_cipher_encrypt_bench, 128 / 8,
+                         AesSafe128Encryptor, AesSafe128Decryptor);
+        aessafe_benches!(aes_192, block_cipher_encrypt_bench, 192 / 8,
+                         AesSafe192Encryptor, AesSafe192Decryptor);
+        aessafe_benches!(aes_256, block_cipher_encrypt_bench, 256 / 8,
+                         AesSafe256Encryptor, AesSafe256Decryptor);
+    }
+
+    mod decrypt {
+        aessafe_benches!(aes_128, block_cipher_decrypt_bench, 128 / 8,
+                         AesSafe128Encryptor, AesSafe128Decryptor);
+        aessafe_benches!(aes_192, block_cipher_decrypt_bench, 192 / 8,
+                         AesSafe192Encryptor, AesSafe192Decryptor);
+        aessafe_benches!(aes_256, block_cipher_decrypt_bench, 256 / 8,
+                         AesSafe256Encryptor, AesSafe256Decryptor);
+    }
+
+    // AES key expansion on its own. `aead::key_setup` measures
+    // `AesGcm::new`, which expands the key twice; this and
+    // `aesni::key_setup` show how much of that is AES.
+    mod key_setup {
+        use aead::generate_key;
+        use crypto::aessafe;
+        use test;
+
+        aessafe_key_setup_bench!(aes_128_encrypt, 128 / 8,
+                                 AesSafe128Encryptor);
+        aessafe_key_setup_bench!(aes_128_decrypt, 128 / 8,
+                                 AesSafe128Decryptor);
+        aessafe_key_setup_bench!(aes_192_encrypt, 192 / 8,
+                                 AesSafe192Encryptor);
+        aessafe_key_setup_bench!(aes_192_decrypt, 192 / 8,
+                                 AesSafe192Decryptor);
+        aessafe_key_setup_bench!(aes_256_encrypt, 256 / 8,
+                                 AesSafe256Encryptor);
+        aessafe_key_setup_bench!(aes_256_decrypt, 256 / 8,
+                                 AesSafe256Decryptor);
+    }
+}
+
+// rust-crypto's AES-NI implementation panics when the CPU doesn't support
+// AES-NI, so these benchmarks are only built when the target CPU is known to
+// support it, e.g. with `RUSTFLAGS="-C target-feature=+aes"`.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+          target_feature = "aes"))]
+mod aesni {
+    use aead::{aes_key_size, generate_key};
+    use crypto::aesni::{AesNiDecryptor, AesNiEncryptor};
+
+    pub fn new(key_len: usize) -> super::BlockCipher<AesNiEncryptor,
+                                                     AesNiDecryptor> {
+        let key = generate_key(key_len);
+        let key_size = aes_key_size(&key);
+        super::BlockCipher {
+            encryptor: AesNiEncryptor::new(key_size, &key),
+            decryptor: AesNiDecryptor::new(key_size, &key),
+        }
+    }
+
+    macro_rules! aesni_benches {
+        ( $name:ident, $bench:ident, $key_len:expr ) => {
+            mod $name {
+                use aes::aesni;
+
+                block_cipher_benches!($bench, aesni::new($key_len));
+            }
+        }
+    }
+
+    macro_rules! aesni_key_setup_bench {
+        ( $name:ident, $key_len:expr, $key_schedule:ident ) => {
+            #[bench]
+            fn $name(b: &mut test::Bencher) {
+                let key = generate_key($key_len);
+                let key_size = aes_key_size(&key);
+                b.iter(|| $key_schedule::new(key_size, &key));
+            }
+        }
+    }
+
+    mod encrypt {
+        aesni_benches!(aes_128, block_cipher_encrypt_bench, 128 / 8);
+        aesni_benches!(aes_192, block_cipher_encrypt_bench, 192 / 8);
+        aesni_benches!(aes_256, block_cipher_encrypt_bench, 256 / 8);
+    }
+
+    mod decrypt {
+        aesni_benches!(aes_128, block_cipher_decrypt_bench, 128 / 8);
+        aesni_benches!(aes_192, block_cipher_decrypt_bench, 192 / 8);
+        aesni_benches!(aes_256, block_cipher_decrypt_bench, 256 / 8);
+    }
+
+    mod key_setup {
+        use aead::{aes_key_size, generate_key};
+        use crypto::aesni::{AesNiDecryptor, AesNiEncryptor};
+        use test;
+
+        aesni_key_setup_bench!(aes_128_encrypt, 128 / 8, AesNiEncryptor);
+        aesni_key_setup_bench!(aes_128_decrypt, 128 / 8, AesNiDecryptor);
+        aesni_key_setup_bench!(aes_192_encrypt, 192 / 8, AesNiEncryptor);
+        aesni_key_setup_bench!(aes_192_decrypt, 192 / 8, AesNiDecryptor);
+        aesni_key_setup_bench!(aes_256_encrypt, 256 / 8, AesNiEncryptor);
+        aesni_key_setup_bench!(aes_256_decrypt, 256 / 8, AesNiDecryptor);
+    }
+}
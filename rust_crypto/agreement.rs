@@ -0,0 +1,55 @@
+// rust-crypto doesn't implement ECDH. Its X25519 keys are just byte arrays, so
+// unlike *ring*'s, a private key isn't consumed by the key agreement.
+
+mod x25519 {
+    use crypto::curve25519::{curve25519, curve25519_base};
+    use rand::{OsRng, Rng};
+    use test;
+
+    const KEY_LEN: usize = 256 / 8;
+
+    fn new_private_key(rng: &mut OsRng) -> [u8; KEY_LEN] {
+        let mut private_key = [0u8; KEY_LEN];
+        rng.fill_bytes(&mut private_key);
+        private_key
+    }
+
+    #[bench]
+    fn generate_key_pair(b: &mut test::Bencher) {
+        let mut rng = OsRng::new().unwrap();
+        b.iter(|| {
+            let private_key = new_private_key(&mut rng);
+            curve25519_base(&private_key)
+        });
+    }
+
+    #[bench]
+    fn generate_private_key(b: &mut test::Bencher) {
+        let mut rng = OsRng::new().unwrap();
+        b.iter(|| new_private_key(&mut rng));
+    }
+
+    #[bench]
+    fn agree_ephemeral(b: &mut test::Bencher) {
+        let mut rng = OsRng::new().unwrap();
+
+        // These operations are done by the peer.
+        let b_public = curve25519_base(&new_private_key(&mut rng));
+
+        let a_private = new_private_key(&mut rng);
+        b.iter(|| curve25519(&a_private, &b_public));
+    }
+
+    #[bench]
+    fn generate_key_pair_and_agree_ephemeral(b: &mut test::Bencher) {
+        let mut rng = OsRng::new().unwrap();
+
+        // These operations are done by the peer.
+        let b_public = curve25519_base(&new_private_key(&mut rng));
+
+        b.iter(|| {
+            let a_private = new_private_key(&mut rng);
+            curve25519(&a_private, &b_public)
+        });
+    }
+}
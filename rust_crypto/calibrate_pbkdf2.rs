@@ -0,0 +1,32 @@
+// Finds, for each PBKDF2 PRF, the number of iterations that rust-crypto can
+// compute in a given amount of time. See `../calibrate_pbkdf2`.
+
+extern crate crypto;
+extern crate crypto_bench;
+
+use crypto::digest::Digest;
+use crypto::{hmac, pbkdf2, sha1, sha2};
+use crypto_bench::pbkdf2::{PASSWORD, SALT};
+use crypto_bench::pbkdf2::calibrate;
+use std::time::Duration;
+
+fn report<D: Digest + Copy>(prf_name: &str, out_len: usize, digest: D,
+                            target: Duration) {
+    calibrate::report("rust_crypto", prf_name, target, out_len,
+                      |iterations, out| {
+        let mut mac = hmac::Hmac::new(digest, PASSWORD);
+        pbkdf2::pbkdf2(&mut mac, SALT, iterations, out);
+    });
+}
+
+fn main() {
+    let target = calibrate::target_from_args();
+    report("hmac_sha1", crypto_bench::SHA1_OUTPUT_LEN, sha1::Sha1::new(),
+           target);
+    report("hmac_sha256", crypto_bench::SHA256_OUTPUT_LEN,
+           sha2::Sha256::new(), target);
+    report("hmac_sha384", crypto_bench::SHA384_OUTPUT_LEN,
+           sha2::Sha384::new(), target);
+    report("hmac_sha512", crypto_bench::SHA512_OUTPUT_LEN,
+           sha2::Sha512::new(), target);
+}
@@ -0,0 +1,110 @@
+use aead::{aes_key_size, generate_key};
+use crypto::aes::KeySize;
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto_bench;
+use crypto_bench::cbc_hmac::BLOCK_LEN;
+
+// rust-crypto's CBC encryptors are constructed with the IV, so a new one, with
+// a new key schedule, has to be constructed for every record. rust-crypto
+// doesn't have in-place operations either, so they are synthesized with a
+// scratch buffer.
+struct CbcHmac<D: Digest> {
+    key: Vec<u8>,
+    key_size: KeySize,
+    hmac: Hmac<D>,
+    scratch: Vec<u8>,
+}
+
+impl<D: Digest> CbcHmac<D> {
+    fn new(key_len: usize, digest: D) -> CbcHmac<D> {
+        let key = generate_key(key_len);
+        let key_size = aes_key_size(&key);
+
+        // The MAC key is as long as the digest output in TLS.
+        let mac_key = generate_key(digest.output_bytes());
+
+        CbcHmac {
+            key: key,
+            key_size: key_size,
+            hmac: Hmac::new(digest, &mac_key),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl<D: Digest> crypto_bench::cbc_hmac::CbcHmac for CbcHmac<D> {
+    fn mac_len(&self) -> usize { self.hmac.output_bytes() }
+
+    fn sign(&mut self, parts: &[&[u8]], out: &mut [u8]) {
+        self.hmac.reset();
+        for part in parts {
+            self.hmac.input(part);
+        }
+        self.hmac.raw_result(out);
+    }
+
+    fn encrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+               len: usize) {
+        use crypto::aes;
+        use crypto::blockmodes::NoPadding;
+        use crypto::buffer::{RefReadBuffer, RefWriteBuffer};
+
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&in_out[..len]);
+        let mut encryptor =
+            aes::cbc_encryptor(self.key_size, &self.key, iv, NoPadding);
+        encryptor.encrypt(&mut RefReadBuffer::new(&self.scratch),
+                          &mut RefWriteBuffer::new(&mut in_out[..len]), true)
+                 .unwrap();
+    }
+
+    fn decrypt(&mut self, iv: &[u8; BLOCK_LEN], in_out: &mut [u8],
+               len: usize) {
+        use crypto::aes;
+        use crypto::blockmodes::NoPadding;
+        use crypto::buffer::{RefReadBuffer, RefWriteBuffer};
+
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&in_out[..len]);
+        let mut decryptor =
+            aes::cbc_decryptor(self.key_size, &self.key, iv, NoPadding);
+        decryptor.decrypt(&mut RefReadBuffer::new(&self.scratch),
+                          &mut RefWriteBuffer::new(&mut in_out[..len]), true)
+                 .unwrap();
+    }
+}
+
+macro_rules! rust_crypto_cbc_hmac_benches {
+    ( $name:ident, $bench:ident, $key_len:expr, $digest:expr ) => {
+        mod $name {
+            use crypto;
+            use super::super::CbcHmac;
+
+            cbc_hmac_benches!($bench, CbcHmac::new($key_len, $digest));
+        }
+    }
+}
+
+mod seal {
+    rust_crypto_cbc_hmac_benches!(aes_128_cbc_sha, cbc_hmac_seal_bench,
+                                  128 / 8, crypto::sha1::Sha1::new());
+    rust_crypto_cbc_hmac_benches!(aes_128_cbc_sha256, cbc_hmac_seal_bench,
+                                  128 / 8, crypto::sha2::Sha256::new());
+    rust_crypto_cbc_hmac_benches!(aes_256_cbc_sha, cbc_hmac_seal_bench,
+                                  256 / 8, crypto::sha1::Sha1::new());
+    rust_crypto_cbc_hmac_benches!(aes_256_cbc_sha256, cbc_hmac_seal_bench,
+                                  256 / 8, crypto::sha2::Sha256::new());
+}
+
+mod open {
+    rust_crypto_cbc_hmac_benches!(aes_128_cbc_sha, cbc_hmac_open_bench,
+                                  128 / 8, crypto::sha1::Sha1::new());
+    rust_crypto_cbc_hmac_benches!(aes_128_cbc_sha256, cbc_hmac_open_bench,
+                                  128 / 8, crypto::sha2::Sha256::new());
+    rust_crypto_cbc_hmac_benches!(aes_256_cbc_sha, cbc_hmac_open_bench,
+                                  256 / 8, crypto::sha1::Sha1::new());
+    rust_crypto_cbc_hmac_benches!(aes_256_cbc_sha256, cbc_hmac_open_bench,
+                                  256 / 8, crypto::sha2::Sha256::new());
+}
@@ -0,0 +1,33 @@
+use crypto::chacha20::ChaCha20;
+use crypto::symmetriccipher::SynchronousStreamCipher;
+use crypto_bench;
+use test;
+
+use aead::generate_key;
+
+// Every message has a new nonce, as in ChaCha20-Poly1305, so the state is set
+// up for every message. rust-crypto's ChaCha20 doesn't work in place, so the
+// output is a separate buffer.
+fn xor_bench(len: usize, b: &mut test::Bencher) {
+    let key = generate_key(256 / 8);
+    let input = vec![0u8; len];
+    let mut output = vec![0u8; len];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut chacha20 = ChaCha20::new(&key, &crypto_bench::aead::NONCE);
+        chacha20.process(&input, &mut output);
+    });
+}
+
+macro_rules! rust_crypto_chacha20_bench {
+    ( $benchmark_name:ident, $len:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::xor_bench($len, b);
+        }
+    }
+}
+
+mod xor {
+    aead_part_benches!(rust_crypto_chacha20_bench);
+}
@@ -0,0 +1,40 @@
+// rust-crypto doesn't implement XTS.
+
+use crypto::aes;
+use test;
+
+use aead::{aes_key_size, generate_key};
+
+// `aes::ctr` chooses the AES-NI or the bitsliced implementation. Like
+// `chacha20::xor_bench`, the state is set up for every buffer, since the IV
+// can only be set when the key is; rust-crypto's CTR mode doesn't work in
+// place, so the output is a separate buffer.
+fn xor_bench(len: usize, key_len: usize, b: &mut test::Bencher) {
+    let key = generate_key(key_len);
+    let key_size = aes_key_size(&key);
+    let iv = [0u8; 128 / 8];
+    let input = vec![0u8; len];
+    let mut output = vec![0u8; len];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut ctr = aes::ctr(key_size, &key, &iv);
+        ctr.process(&input, &mut output);
+    });
+}
+
+macro_rules! rust_crypto_ctr_bench {
+    ( $benchmark_name:ident, $len:expr, $key_len:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            super::xor_bench($len, $key_len, b);
+        }
+    }
+}
+
+mod aes_128 {
+    bulk_benches!(rust_crypto_ctr_bench, 128 / 8);
+}
+
+mod aes_256 {
+    bulk_benches!(rust_crypto_ctr_bench, 256 / 8);
+}
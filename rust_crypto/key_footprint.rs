@@ -0,0 +1,37 @@
+// Reports how much memory each of rust-crypto's `many_keys::SealingKey`s
+// takes, for comparison with the `many_keys` benchmarks, and how much the
+// `AesGcm` or `ChaCha20Poly1305` that it constructs for each record takes.
+// rust-crypto's AEADs are keyed for a single nonce, so the latter is what a
+// key costs while a record is being sealed. See `../README.md`.
+
+extern crate crypto;
+extern crate crypto_bench;
+extern crate crypto_bench_rust_crypto;
+
+use crypto::aes::KeySize;
+use crypto::aes_gcm::AesGcm;
+use crypto::chacha20poly1305::ChaCha20Poly1305;
+use crypto_bench::aead::NONCE;
+use crypto_bench::footprint::{self, CountingAllocator};
+use crypto_bench_rust_crypto::many_keys::SealingKey;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn report_aes_gcm(name: &str, key_size: KeySize, key_len: usize) {
+    footprint::report("rust-crypto", name,
+                      || SealingKey::aes_gcm(key_len));
+    let key = vec![0u8; key_len];
+    footprint::report("rust-crypto", &format!("{}::AesGcm", name),
+                      || AesGcm::new(key_size, &key, &NONCE, &[]));
+}
+
+fn main() {
+    report_aes_gcm("aes_128_gcm", KeySize::KeySize128, 128 / 8);
+    report_aes_gcm("aes_256_gcm", KeySize::KeySize256, 256 / 8);
+    footprint::report("rust-crypto", "chacha20_poly1305_old",
+                      SealingKey::chacha20_poly1305_old);
+    let key = [0u8; 256 / 8];
+    footprint::report("rust-crypto", "chacha20_poly1305_old::ChaCha20Poly1305",
+                      || ChaCha20Poly1305::new(&key, &NONCE[4..], &[]));
+}
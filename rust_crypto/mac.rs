@@ -0,0 +1,37 @@
+// rust-crypto doesn't implement CMAC.
+
+// GMAC is AES-GCM with an empty plaintext, where the input is the additional
+// data. Like the AEAD benchmarks, these include key setup because the
+// additional data is passed to `AesGcm::new`.
+mod gmac {
+    macro_rules! rust_crypto_gmac_benches {
+        ( $name:ident, $key_len:expr ) => {
+            mod $name {
+                use aead::{aes_key_size, generate_key};
+                use crypto::aead::AeadEncryptor;
+                use crypto::aes_gcm::AesGcm;
+                use crypto_bench;
+
+                mac_benches!(key, generate_key($key_len), input, {
+                    let mut tag = [0u8; 16];
+                    let mut gmac =
+                        AesGcm::new(aes_key_size(&key), &key,
+                                    &crypto_bench::aead::NONCE, input);
+                    gmac.encrypt(&[], &mut [], &mut tag);
+                });
+            }
+        }
+    }
+
+    rust_crypto_gmac_benches!(aes_128, 128 / 8);
+    rust_crypto_gmac_benches!(aes_256, 256 / 8);
+}
+
+// The GHASH key is expanded once and then copied for every message.
+mod ghash {
+    use aead::generate_key;
+    use crypto::ghash::Ghash;
+
+    mac_benches!(key, Ghash::new(&generate_key(16)), input,
+                 key.input_a(input).result());
+}
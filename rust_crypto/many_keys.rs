@@ -0,0 +1,79 @@
+use aead::{aes_key_size, generate_key};
+use crypto::aead::AeadEncryptor;
+use crypto::aes_gcm::AesGcm;
+use crypto::chacha20poly1305::ChaCha20Poly1305;
+use crypto_bench;
+use crypto_bench::many_keys::RECORD_LEN;
+use record_layer::{Algorithm, TAG_LEN};
+
+// rust-crypto's AEADs are keyed for a single nonce, so a session can only
+// keep the key bytes, and `AesGcm::new` or `ChaCha20Poly1305::new` computes
+// the key schedule for every record; see `aead::key_setup`. `key_footprint`
+// measures both this type and the per-record state.
+pub struct SealingKey {
+    algorithm: Algorithm,
+    key: Vec<u8>,
+}
+
+impl SealingKey {
+    pub fn aes_gcm(key_len: usize) -> SealingKey {
+        let key = generate_key(key_len);
+        SealingKey {
+            algorithm: Algorithm::AesGcm(aes_key_size(&key)),
+            key: key,
+        }
+    }
+
+    pub fn chacha20_poly1305_old() -> SealingKey {
+        SealingKey {
+            algorithm: Algorithm::ChaCha20Poly1305Old,
+            key: generate_key(32),
+        }
+    }
+}
+
+impl crypto_bench::many_keys::SealingKey for SealingKey {
+    fn tag_len(&self) -> usize { TAG_LEN }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        let len = in_out.len() - TAG_LEN;
+        let (in_out, tag) = in_out.split_at_mut(len);
+        // rust-crypto doesn't encrypt in place, but the records are only
+        // `RECORD_LEN` bytes long, so the ciphertext can go on the stack.
+        let mut out = [0u8; RECORD_LEN];
+        let out = &mut out[..len];
+        match self.algorithm {
+            Algorithm::AesGcm(key_size) => {
+                AesGcm::new(key_size, &self.key, nonce, ad)
+                    .encrypt(in_out, out, tag);
+            },
+            Algorithm::ChaCha20Poly1305Old => {
+                // The old construction's 64-bit nonce is the part of the
+                // 96-bit nonce that varies.
+                ChaCha20Poly1305::new(&self.key, &nonce[4..], ad)
+                    .encrypt(in_out, out, tag);
+            },
+        }
+        in_out.copy_from_slice(out);
+        Ok(())
+    }
+}
+
+mod aes_128_gcm {
+    use super::SealingKey;
+
+    many_keys_benches!(SealingKey::aes_gcm(128 / 8));
+}
+
+mod aes_256_gcm {
+    use super::SealingKey;
+
+    many_keys_benches!(SealingKey::aes_gcm(256 / 8));
+}
+
+mod chacha20_poly1305_old {
+    use super::SealingKey;
+
+    many_keys_benches!(SealingKey::chacha20_poly1305_old());
+}
@@ -0,0 +1,20 @@
+// Prints the peak memory use of rust-crypto's bcrypt and scrypt for each set
+// of parameters that is benchmarked. See `crypto_bench::peak_memory`.
+
+#[macro_use]
+extern crate crypto_bench;
+extern crate crypto;
+
+use crypto::{bcrypt, scrypt};
+
+fn main() {
+    bcrypt_params!(bcrypt_peak_memory, "rust-crypto", cost, out,
+                   bcrypt::bcrypt(cost, crypto_bench::password_hash::SALT,
+                                  crypto_bench::password_hash::PASSWORD,
+                                  &mut out));
+    scrypt_params!(scrypt_peak_memory, "rust-crypto", log_n, r, p, out, {
+        let params = scrypt::ScryptParams::new(log_n, r, p);
+        scrypt::scrypt(crypto_bench::password_hash::PASSWORD,
+                       crypto_bench::password_hash::SALT, &params, &mut out);
+    });
+}
@@ -0,0 +1,52 @@
+use crypto::mac::Mac;
+use crypto::poly1305::Poly1305;
+use crypto_bench::poly1305::{KEY_LEN, TAG_LEN, UPDATE_LEN};
+use test;
+
+use aead::generate_key;
+
+// Poly1305 keys are one-time keys, so the state is set up for every message.
+
+fn one_shot_bench(len: usize, b: &mut test::Bencher) {
+    let key = generate_key(KEY_LEN);
+    let input = vec![0u8; len];
+    let mut tag = [0u8; TAG_LEN];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut poly1305 = Poly1305::new(&key);
+        poly1305.input(&input);
+        poly1305.raw_result(&mut tag);
+    });
+}
+
+fn incremental_bench(len: usize, b: &mut test::Bencher) {
+    let key = generate_key(KEY_LEN);
+    let input = vec![0u8; len];
+    let mut tag = [0u8; TAG_LEN];
+    b.bytes = len as u64;
+    b.iter(|| {
+        let mut poly1305 = Poly1305::new(&key);
+        for piece in input.chunks(UPDATE_LEN) {
+            poly1305.input(piece);
+        }
+        poly1305.raw_result(&mut tag);
+    });
+}
+
+macro_rules! rust_crypto_poly1305_bench {
+    ( $benchmark_name:ident, $len:expr, $f:expr ) => {
+        #[bench]
+        fn $benchmark_name(b: &mut test::Bencher) {
+            $f($len, b);
+        }
+    }
+}
+
+mod one_shot {
+    aead_part_benches!(rust_crypto_poly1305_bench, super::one_shot_bench);
+}
+
+mod incremental {
+    aead_part_benches!(rust_crypto_poly1305_bench,
+                      super::incremental_bench);
+}
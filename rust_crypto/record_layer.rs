@@ -0,0 +1,119 @@
+use aead::{aes_key_size, generate_key};
+use crypto::aes::KeySize;
+use crypto_bench;
+
+pub enum Algorithm {
+    AesGcm(KeySize),
+    ChaCha20Poly1305Old,
+}
+
+// rust-crypto doesn't have in-place operations, so the record layer has to
+// synthesize them with a scratch buffer.
+pub struct Aead {
+    algorithm: Algorithm,
+    key: Vec<u8>,
+    scratch: Vec<u8>,
+}
+
+pub const TAG_LEN: usize = 128 / 8;
+
+impl Aead {
+    pub fn aes_gcm(key_len: usize) -> Aead {
+        let key = generate_key(key_len);
+        Aead {
+            algorithm: Algorithm::AesGcm(aes_key_size(&key)),
+            key: key,
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn chacha20_poly1305_old() -> Aead {
+        Aead {
+            algorithm: Algorithm::ChaCha20Poly1305Old,
+            key: generate_key(32),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl crypto_bench::record_layer::Aead for Aead {
+    fn tag_len(&self) -> usize { TAG_LEN }
+
+    fn seal_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        use crypto::aead::AeadEncryptor;
+        use crypto::aes_gcm::AesGcm;
+        use crypto::chacha20poly1305::ChaCha20Poly1305;
+
+        let len = in_out.len() - TAG_LEN;
+        let (in_out, tag) = in_out.split_at_mut(len);
+        self.scratch.resize(len, 0);
+        match self.algorithm {
+            Algorithm::AesGcm(key_size) => {
+                AesGcm::new(key_size, &self.key, nonce, ad)
+                    .encrypt(in_out, &mut self.scratch, tag);
+            },
+            Algorithm::ChaCha20Poly1305Old => {
+                // The old construction's 64-bit nonce is the part of the
+                // 96-bit nonce that varies.
+                ChaCha20Poly1305::new(&self.key, &nonce[4..], ad)
+                    .encrypt(in_out, &mut self.scratch, tag);
+            },
+        }
+        in_out.copy_from_slice(&self.scratch);
+        Ok(())
+    }
+
+    fn open_in_place(&mut self, nonce: &[u8; 96 / 8], ad: &[u8],
+                     in_out: &mut [u8]) -> Result<(), ()> {
+        use crypto::aead::AeadDecryptor;
+        use crypto::aes_gcm::AesGcm;
+        use crypto::chacha20poly1305::ChaCha20Poly1305;
+
+        let len = in_out.len() - TAG_LEN;
+        let (in_out, tag) = in_out.split_at_mut(len);
+        self.scratch.clear();
+        self.scratch.extend_from_slice(in_out);
+        let opened = match self.algorithm {
+            Algorithm::AesGcm(key_size) => {
+                AesGcm::new(key_size, &self.key, nonce, ad)
+                    .decrypt(&self.scratch, in_out, tag)
+            },
+            Algorithm::ChaCha20Poly1305Old => {
+                ChaCha20Poly1305::new(&self.key, &nonce[4..], ad)
+                    .decrypt(&self.scratch, in_out, tag)
+            },
+        };
+        if opened { Ok(()) } else { Err(()) }
+    }
+}
+
+macro_rules! rust_crypto_record_layer_benches {
+    ( $name:ident, $bench:ident, $new_aead:expr ) => {
+        mod $name {
+            use super::super::Aead;
+
+            record_layer_benches!($bench, $new_aead);
+        }
+    }
+}
+
+mod seal {
+    rust_crypto_record_layer_benches!(aes_128_gcm, record_layer_seal_bench,
+                                      Aead::aes_gcm(128 / 8));
+    rust_crypto_record_layer_benches!(aes_256_gcm, record_layer_seal_bench,
+                                      Aead::aes_gcm(256 / 8));
+    rust_crypto_record_layer_benches!(chacha20_poly1305_old,
+                                      record_layer_seal_bench,
+                                      Aead::chacha20_poly1305_old());
+}
+
+mod open {
+    rust_crypto_record_layer_benches!(aes_128_gcm, record_layer_open_bench,
+                                      Aead::aes_gcm(128 / 8));
+    rust_crypto_record_layer_benches!(aes_256_gcm, record_layer_open_bench,
+                                      Aead::aes_gcm(256 / 8));
+    rust_crypto_record_layer_benches!(chacha20_poly1305_old,
+                                      record_layer_open_bench,
+                                      Aead::chacha20_poly1305_old());
+}
@@ -9,6 +9,16 @@ extern crate crypto;
 extern crate rand;
 
 mod aead;
+mod agreement;
+mod aes;
+mod cbc_hmac;
+mod chacha20;
+mod ctr;
+mod mac;
+pub mod many_keys;
+mod poly1305;
+mod record_layer;
+mod stream;
 
 mod digest {
     macro_rules! rust_crypto_digest_benches {
@@ -43,28 +53,90 @@ mod digest {
 }
 
 mod pbkdf2 {
-    use crypto::{hmac, pbkdf2, sha1, sha2};
-    use crypto_bench;
-    use test;
-
-    pbkdf2_bench!(hmac_sha1, 20, out, {
-        let mut mac = hmac::Hmac::new(sha1::Sha1::new(),
-                                      &crypto_bench::pbkdf2::PASSWORD);
-        pbkdf2::pbkdf2(&mut mac, &crypto_bench::pbkdf2::SALT,
-                       crypto_bench::pbkdf2::ITERATIONS, &mut out);
-    });
-
-    pbkdf2_bench!(hmac_sha256, 32, out, {
-        let mut mac = hmac::Hmac::new(sha2::Sha256::new(),
-                                      &crypto_bench::pbkdf2::PASSWORD);
-        pbkdf2::pbkdf2(&mut mac, &crypto_bench::pbkdf2::SALT,
-                       crypto_bench::pbkdf2::ITERATIONS, &mut out);
-    });
-
-    pbkdf2_bench!(hmac_sha512, 64, out, {
-        let mut mac = hmac::Hmac::new(sha2::Sha512::new(),
-                                      &crypto_bench::pbkdf2::PASSWORD);
-        pbkdf2::pbkdf2(&mut mac, &crypto_bench::pbkdf2::SALT,
-                       crypto_bench::pbkdf2::ITERATIONS, &mut out);
-    });
+    macro_rules! rust_crypto_pbkdf2_benches {
+        ( $name:ident, $output_len:expr, $digest:expr) => {
+            mod $name {
+                use crypto;
+                use crypto::{hmac, pbkdf2};
+                use crypto_bench;
+
+                pbkdf2_benches!($output_len, out, {
+                    let mut mac =
+                        hmac::Hmac::new($digest,
+                                        &crypto_bench::pbkdf2::PASSWORD);
+                    pbkdf2::pbkdf2(&mut mac, &crypto_bench::pbkdf2::SALT,
+                                   crypto_bench::pbkdf2::ITERATIONS, &mut out);
+                });
+            }
+        }
+    }
+
+    rust_crypto_pbkdf2_benches!(hmac_sha1, crypto_bench::SHA1_OUTPUT_LEN,
+                                crypto::sha1::Sha1::new());
+    rust_crypto_pbkdf2_benches!(hmac_sha256, crypto_bench::SHA256_OUTPUT_LEN,
+                                crypto::sha2::Sha256::new());
+    rust_crypto_pbkdf2_benches!(hmac_sha384, crypto_bench::SHA384_OUTPUT_LEN,
+                                crypto::sha2::Sha384::new());
+    rust_crypto_pbkdf2_benches!(hmac_sha512, crypto_bench::SHA512_OUTPUT_LEN,
+                                crypto::sha2::Sha512::new());
+}
+
+mod password_hash {
+    // rust-crypto doesn't implement Argon2.
+
+    mod bcrypt {
+        use crypto::bcrypt;
+        use crypto_bench;
+
+        bcrypt_benches!(cost, out,
+                        bcrypt::bcrypt(cost,
+                                       crypto_bench::password_hash::SALT,
+                                       crypto_bench::password_hash::PASSWORD,
+                                       &mut out));
+    }
+
+    mod scrypt {
+        use crypto::scrypt;
+        use crypto_bench;
+
+        scrypt_benches!(log_n, r, p, out, {
+            let params = scrypt::ScryptParams::new(log_n, r, p);
+            scrypt::scrypt(crypto_bench::password_hash::PASSWORD,
+                           crypto_bench::password_hash::SALT, &params,
+                           &mut out);
+        });
+    }
+}
+
+mod tls12_prf {
+    macro_rules! rust_crypto_tls12_prf_benches {
+        ( $name:ident, $output_len:expr, $digest:expr,
+          $test_vector:expr) => {
+            mod $name {
+                use crypto::{hmac, sha2};
+                use crypto::mac::Mac;
+                use crypto_bench;
+
+                tls12_prf_benches!($test_vector, secret, label, seed, out, {
+                    let mut mac = hmac::Hmac::new($digest, secret);
+                    crypto_bench::tls12_prf::p_hash(
+                        &mut out, $output_len, label, seed,
+                        |parts, hmac_out| {
+                            mac.reset();
+                            for part in parts {
+                                mac.input(part);
+                            }
+                            mac.raw_result(hmac_out);
+                        });
+                });
+            }
+        }
+    }
+
+    rust_crypto_tls12_prf_benches!(
+        p_sha256, crypto_bench::SHA256_OUTPUT_LEN, sha2::Sha256::new(),
+        crypto_bench::tls12_prf::P_SHA256_TEST_VECTOR);
+    rust_crypto_tls12_prf_benches!(
+        p_sha384, crypto_bench::SHA384_OUTPUT_LEN, sha2::Sha384::new(),
+        crypto_bench::tls12_prf::P_SHA384_TEST_VECTOR);
 }
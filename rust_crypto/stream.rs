@@ -0,0 +1,30 @@
+// The STREAM benchmarks use the same `crypto_bench::record_layer::Aead` as the
+// record layer benchmarks.
+
+macro_rules! rust_crypto_stream_benches {
+    ( $name:ident, $bench:ident, $new_aead:expr ) => {
+        mod $name {
+            use record_layer::Aead;
+
+            stream_benches!($bench, $new_aead);
+        }
+    }
+}
+
+mod seal {
+    rust_crypto_stream_benches!(aes_128_gcm, stream_seal_bench,
+                                Aead::aes_gcm(128 / 8));
+    rust_crypto_stream_benches!(aes_256_gcm, stream_seal_bench,
+                                Aead::aes_gcm(256 / 8));
+    rust_crypto_stream_benches!(chacha20_poly1305_old, stream_seal_bench,
+                                Aead::chacha20_poly1305_old());
+}
+
+mod open {
+    rust_crypto_stream_benches!(aes_128_gcm, stream_open_bench,
+                                Aead::aes_gcm(128 / 8));
+    rust_crypto_stream_benches!(aes_256_gcm, stream_open_bench,
+                                Aead::aes_gcm(256 / 8));
+    rust_crypto_stream_benches!(chacha20_poly1305_old, stream_open_bench,
+                                Aead::chacha20_poly1305_old());
+}
@@ -0,0 +1,77 @@
+// Opening in place overwrites the ciphertext with the plaintext, so the
+// ciphertext has to be restored before every open. `test::Bencher` would time
+// that copy too, so these benchmarks use Criterion's `iter_batched_ref`, which
+// copies a batch of ciphertexts before timing the opens that overwrite them.
+// The tag is kept separately, so only the ciphertext is copied.
+
+#[macro_use]
+extern crate criterion;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate aead;
+extern crate crypto_bench_rustcrypto_aeads;
+
+use aead::{AeadInPlace, Nonce};
+use criterion::{BatchSize, Criterion, Throughput};
+use crypto_bench_rustcrypto_aeads::aead::{Aes128Ccm, Aes128Ccm8,
+                                          Aes128GcmSiv, Aes256GcmSiv,
+                                          XChaCha20Poly1305, lengths,
+                                          new_key};
+
+fn open_in_place_bench<A>(c: &mut Criterion, name: &str, key: A,
+                          scenario: &str, chunk_len: usize, ad: &[u8])
+                          where A: AeadInPlace {
+    let lengths = lengths::<A>();
+    let nonce = Nonce::<A>::from_slice(lengths.nonce());
+
+    let mut sealed = vec![0u8; chunk_len];
+    let sealed_tag =
+        key.encrypt_in_place_detached(nonce, ad, &mut sealed).unwrap();
+
+    let mut group = c.benchmark_group(
+        format!("aead::open_in_place::{}::{}", name, scenario));
+    for &tampering in crypto_bench::aead::tamperings(chunk_len) {
+        let mut ciphertext = sealed.clone();
+        let mut tag = sealed_tag.clone();
+        let mut ad = ad.to_vec();
+        crypto_bench::aead::tamper(tampering, &mut ad, &mut ciphertext,
+                                   &mut tag);
+
+        let counts = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                         lengths.tag);
+        let should_open = tampering == crypto_bench::aead::Tampering::None;
+        for (bench_name, counter) in
+                crypto_bench::aead::open_benches(tampering, chunk_len) {
+            group.throughput(Throughput::Bytes(counts.bytes(counter)));
+            group.bench_function(bench_name, |b| {
+                b.iter_batched_ref(|| ciphertext.clone(), |in_out| {
+                    let result =
+                        key.decrypt_in_place_detached(nonce, &ad, in_out, &tag);
+                    assert_eq!(result.is_ok(), should_open);
+                }, BatchSize::SmallInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+fn open_in_place(c: &mut Criterion) {
+    long_message_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                                 "aes_128_gcm_siv",
+                                 new_key::<Aes128GcmSiv>());
+    long_message_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                                 "aes_256_gcm_siv",
+                                 new_key::<Aes256GcmSiv>());
+    long_message_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                                 "xchacha20_poly1305",
+                                 new_key::<XChaCha20Poly1305>());
+    long_message_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                                 "aes_128_ccm", new_key::<Aes128Ccm>());
+    long_message_aead_scenarios!(aead_scenario_call, open_in_place_bench, c,
+                                 "aes_128_ccm_8", new_key::<Aes128Ccm8>());
+}
+
+criterion_group!(benches, open_in_place);
+criterion_main!(benches);
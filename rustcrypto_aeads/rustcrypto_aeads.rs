@@ -0,0 +1,83 @@
+#![feature(test)]
+
+extern crate test;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate aead as aead_crate;
+extern crate aes;
+extern crate aes_gcm_siv;
+extern crate ccm;
+extern crate chacha20poly1305;
+
+// The benchmarks are generic over the RustCrypto `aead` traits, and use the
+// nonce and tag lengths of each algorithm.
+//
+// AES-GCM-SIV (RFC 8452) is resistant to nonce misuse. Its per-nonce key
+// derivation is part of every seal and open, so it is included in these
+// benchmarks; only the key schedule of the key-generating key is excluded.
+pub mod aead {
+    use aead_crate::{AeadInPlace, KeyInit, Nonce, OsRng};
+    use aead_crate::generic_array::typenum::Unsigned;
+    pub use aes_gcm_siv::{Aes128GcmSiv, Aes256GcmSiv};
+    pub use chacha20poly1305::XChaCha20Poly1305;
+    use crypto_bench;
+    use test;
+
+    pub fn lengths<A>() -> crypto_bench::aead::Lengths where A: AeadInPlace {
+        crypto_bench::aead::Lengths {
+            nonce: A::NonceSize::to_usize(),
+            tag: A::TagSize::to_usize(),
+        }
+    }
+
+    pub fn new_key<A>() -> A where A: KeyInit {
+        A::new(&A::generate_key(OsRng))
+    }
+
+    fn seal_in_place_bench<A>(key: A, chunk_len: usize, ad: &[u8],
+                              counter: crypto_bench::aead::Counter,
+                              b: &mut test::Bencher) where A: AeadInPlace {
+        let lengths = lengths::<A>();
+        let nonce = Nonce::<A>::from_slice(lengths.nonce());
+
+        let mut in_out = vec![0u8; chunk_len];
+
+        b.bytes = crypto_bench::aead::ByteCounts::new(chunk_len, ad.len(),
+                                                      lengths.tag)
+            .bytes(counter);
+
+        b.iter(|| {
+            let _ = key.encrypt_in_place_detached(nonce, ad, &mut in_out)
+                       .unwrap();
+        });
+    }
+
+    // AES-CCM with the nonce and tag lengths of `crypto_bench::aead::AES_CCM`
+    // and `crypto_bench::aead::AES_CCM_8`.
+    pub type Aes128Ccm = ::ccm::Ccm<::aes::Aes128, ::ccm::consts::U16,
+                                    ::ccm::consts::U12>;
+    pub type Aes128Ccm8 = ::ccm::Ccm<::aes::Aes128, ::ccm::consts::U8,
+                                     ::ccm::consts::U12>;
+
+    mod seal_in_place {
+        use super::seal_in_place_bench;
+
+        long_message_aead_benches!(
+            aes_128_gcm_siv, aead_seal_in_place_bench, seal_in_place_bench,
+            ::aead::new_key::<::aead::Aes128GcmSiv>());
+        long_message_aead_benches!(
+            aes_256_gcm_siv, aead_seal_in_place_bench, seal_in_place_bench,
+            ::aead::new_key::<::aead::Aes256GcmSiv>());
+        long_message_aead_benches!(
+            xchacha20_poly1305, aead_seal_in_place_bench, seal_in_place_bench,
+            ::aead::new_key::<::aead::XChaCha20Poly1305>());
+        long_message_aead_benches!(
+            aes_128_ccm, aead_seal_in_place_bench, seal_in_place_bench,
+            ::aead::new_key::<::aead::Aes128Ccm>());
+        long_message_aead_benches!(
+            aes_128_ccm_8, aead_seal_in_place_bench, seal_in_place_bench,
+            ::aead::new_key::<::aead::Aes128Ccm8>());
+    }
+}
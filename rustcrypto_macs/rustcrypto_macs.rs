@@ -0,0 +1,67 @@
+#![feature(test)]
+
+extern crate test;
+
+#[macro_use]
+extern crate crypto_bench;
+
+extern crate aes;
+extern crate cmac;
+extern crate getrandom;
+extern crate ghash;
+extern crate polyval;
+
+fn generate_key(key_len: usize) -> Vec<u8> {
+    let mut key = vec![0u8; key_len];
+    getrandom::getrandom(&mut key).unwrap();
+    key
+}
+
+// The key schedule isn't measured: each benchmark keys one instance up front
+// and then either resets it or clones it for every message.
+mod mac {
+    macro_rules! rustcrypto_cmac_benches {
+        ( $name:ident, $cipher:ty, $key_len:expr ) => {
+            mod $name {
+                use cmac::{Cmac, Mac};
+                use generate_key;
+
+                mac_benches!(key, {
+                    <Cmac<$cipher> as Mac>::new_from_slice(
+                        &generate_key($key_len)).unwrap()
+                }, input, {
+                    Mac::update(&mut key, input);
+                    key.finalize_reset()
+                });
+            }
+        }
+    }
+
+    mod cmac {
+        rustcrypto_cmac_benches!(aes_128, ::aes::Aes128, 128 / 8);
+        rustcrypto_cmac_benches!(aes_256, ::aes::Aes256, 256 / 8);
+    }
+
+    // GHASH and POLYVAL are the universal hashes of AES-GCM and AES-GCM-SIV.
+    // `GHash` can't be reset, so both are cloned instead.
+    macro_rules! rustcrypto_universal_hash_benches {
+        ( $name:ident, $hash:ty ) => {
+            mod $name {
+                use generate_key;
+                use ghash::universal_hash::{KeyInit, UniversalHash};
+
+                mac_benches!(key, {
+                    <$hash as KeyInit>::new_from_slice(&generate_key(16))
+                        .unwrap()
+                }, input, {
+                    let mut hash = key.clone();
+                    hash.update_padded(input);
+                    hash.finalize()
+                });
+            }
+        }
+    }
+
+    rustcrypto_universal_hash_benches!(ghash, ::ghash::GHash);
+    rustcrypto_universal_hash_benches!(polyval, ::polyval::Polyval);
+}